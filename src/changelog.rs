@@ -0,0 +1,350 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::conventional::{self, Header};
+use crate::git::Commit;
+
+const PREAMBLE: &str =
+    "# Changelog\n\nAll notable changes to this project will be documented in this file.\n";
+
+/// Section order from Keep a Changelog; unknown sections (from a custom pattern) follow.
+const SECTION_ORDER: [&str; 6] = [
+    "Added",
+    "Changed",
+    "Deprecated",
+    "Removed",
+    "Fixed",
+    "Security",
+];
+
+static UNRELEASED_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?im)^## \[?unreleased\]?.*$").unwrap());
+
+/// How commits are sorted into changelog sections.
+pub enum Grouping {
+    /// Map Conventional Commit types onto Keep a Changelog sections.
+    Conventional,
+    /// Match the subject against a regex; the `section` group (or the first group)
+    /// names the section and the optional `description` group is the entry text.
+    Pattern(Regex),
+}
+impl Grouping {
+    fn classify(&self, commit: &Commit) -> Option<(String, String)> {
+        match self {
+            Grouping::Conventional => {
+                let header = match Header::parse(&commit.subject) {
+                    Some(header) => header,
+                    None => return Some(("Changed".to_string(), commit.subject.clone())),
+                };
+                let breaking = conventional::is_breaking(commit);
+                let section = match header.kind.as_str() {
+                    "feat" => "Added",
+                    "fix" => "Fixed",
+                    "perf" | "refactor" => "Changed",
+                    "deprecate" => "Deprecated",
+                    "revert" => "Removed",
+                    "security" => "Security",
+                    _ if breaking => "Changed",
+                    _ => return None,
+                };
+                let mut entry = match header.scope {
+                    Some(scope) => format!("**{}:** {}", scope, header.description),
+                    None => header.description,
+                };
+                if breaking {
+                    entry = format!("**BREAKING** {}", entry);
+                }
+                Some((section.to_string(), entry))
+            }
+            Grouping::Pattern(regex) => match regex.captures(&commit.subject) {
+                Some(c) => {
+                    let section = c.name("section").or_else(|| c.get(1));
+                    let entry = c
+                        .name("description")
+                        .map_or(&*commit.subject, |d| d.as_str());
+                    Some((
+                        section.map_or("Other", |s| s.as_str()).to_string(),
+                        entry.to_string(),
+                    ))
+                }
+                None => Some(("Other".to_string(), commit.subject.clone())),
+            },
+        }
+    }
+}
+
+pub struct Section {
+    pub title: String,
+    pub entries: Vec<String>,
+}
+
+pub struct Release {
+    pub version: String,
+    pub date: Option<String>,
+    pub sections: Vec<Section>,
+}
+impl Release {
+    /// Build a release from `commits` as returned by `git_log` (newest first).
+    pub fn from_commits(
+        version: String,
+        date: Option<String>,
+        commits: &[Commit],
+        grouping: &Grouping,
+    ) -> Self {
+        let mut sections: Vec<Section> = Vec::new();
+        for commit in commits.iter().rev() {
+            let (title, entry) = match grouping.classify(commit) {
+                Some(x) => x,
+                None => continue,
+            };
+            let entry = format!("{} ({})", entry, commit.hash);
+            match sections.iter_mut().find(|s| s.title == title) {
+                Some(section) => section.entries.push(entry),
+                None => sections.push(Section {
+                    title,
+                    entries: vec![entry],
+                }),
+            }
+        }
+        sections.sort_by_key(|s| {
+            SECTION_ORDER
+                .iter()
+                .position(|&t| t == s.title)
+                .unwrap_or(SECTION_ORDER.len())
+        });
+        Self {
+            version,
+            date,
+            sections,
+        }
+    }
+
+    pub fn heading(&self) -> String {
+        match &self.date {
+            Some(date) => format!("## v{} - {}", self.version, date),
+            None => format!("## v{}", self.version),
+        }
+    }
+
+    /// The section body, without the `##` heading.
+    pub fn body(&self) -> String {
+        self.sections
+            .iter()
+            .map(|section| {
+                let entries: Vec<String> = section
+                    .entries
+                    .iter()
+                    .map(|e| format!("- {}\n", e))
+                    .collect();
+                format!("### {}\n\n{}", section.title, entries.concat())
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let body = self.body();
+        if body.is_empty() {
+            format!("{}\n", self.heading())
+        } else {
+            format!("{}\n\n{}", self.heading(), body)
+        }
+    }
+}
+
+/// Read the section for `version` back out of a changelog. Entries that come
+/// before any `###` heading are collected into a section with an empty title.
+pub fn find_release(changelog: &str, version: &str) -> Option<Release> {
+    let heading = Regex::new(&format!(
+        r"(?m)^## \[?v?{}\]?(?:\s+-\s+(\S+))?[ \t]*$",
+        regex::escape(version)
+    ))
+    .unwrap();
+    let c = heading.captures(changelog)?;
+    let rest = &changelog[c.get(0).unwrap().end()..];
+    let body = &rest[..rest.find("\n## ").map_or(rest.len(), |i| i + 1)];
+
+    let mut sections: Vec<Section> = Vec::new();
+    for line in body.lines() {
+        if let Some(title) = line.strip_prefix("### ") {
+            sections.push(Section {
+                title: title.trim().to_string(),
+                entries: Vec::new(),
+            });
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        if sections.is_empty() {
+            sections.push(Section {
+                title: String::new(),
+                entries: Vec::new(),
+            });
+        }
+        let section = sections.last_mut().unwrap();
+        let item = line.strip_prefix("- ").or_else(|| line.strip_prefix("* "));
+        match (item, section.entries.last_mut()) {
+            (None, Some(last)) => {
+                last.push(' ');
+                last.push_str(line.trim());
+            }
+            (item, _) => section
+                .entries
+                .push(item.unwrap_or(line).trim().to_string()),
+        }
+    }
+    Some(Release {
+        version: version.to_string(),
+        date: c.get(1).map(|d| d.as_str().to_string()),
+        sections,
+    })
+}
+
+/// Add `release` to the changelog text: an existing `## Unreleased` section is
+/// promoted (keeping its hand-written notes if it has any), otherwise the release
+/// is inserted above the newest version section.
+pub fn update(changelog: &str, release: &Release) -> String {
+    if let Some(m) = UNRELEASED_REGEX.find(changelog) {
+        let rest = &changelog[m.end()..];
+        let body_len = rest.find("\n## ").map_or(rest.len(), |i| i + 1);
+        if !rest[..body_len].trim().is_empty() {
+            return format!("{}{}{}", &changelog[..m.start()], release.heading(), rest);
+        }
+        let after = &rest[body_len..];
+        let separator = if after.is_empty() { "" } else { "\n" };
+        return format!(
+            "{}{}{}{}",
+            &changelog[..m.start()],
+            release.to_markdown(),
+            separator,
+            after
+        );
+    }
+
+    if changelog.trim().is_empty() {
+        return format!("{}\n{}", PREAMBLE, release.to_markdown());
+    }
+    let insert_at = if changelog.starts_with("## ") {
+        Some(0)
+    } else {
+        changelog.find("\n## ").map(|i| i + 1)
+    };
+    match insert_at {
+        Some(i) => format!(
+            "{}{}\n{}",
+            &changelog[..i],
+            release.to_markdown(),
+            &changelog[i..]
+        ),
+        None => format!("{}\n\n{}", changelog.trim_end(), release.to_markdown()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(hash: &str, subject: &str) -> Commit {
+        Commit {
+            hash: hash.to_string(),
+            subject: subject.to_string(),
+            body: String::new(),
+        }
+    }
+
+    fn release() -> Release {
+        let commits = vec![
+            commit("ccc", "fix(parser): handle tabs"),
+            commit("bbb", "chore: bump deps"),
+            commit("aaa", "feat: add push"),
+        ];
+        Release::from_commits(
+            "1.1.0".to_string(),
+            Some("2026-10-18".to_string()),
+            &commits,
+            &Grouping::Conventional,
+        )
+    }
+
+    #[test]
+    fn release_from_conventional_commits() {
+        assert_eq!(
+            release().to_markdown(),
+            "## v1.1.0 - 2026-10-18\n\n### Added\n\n- add push (aaa)\n\n### Fixed\n\n- **parser:** handle tabs (ccc)\n"
+        );
+    }
+
+    #[test]
+    fn release_from_pattern() {
+        let commits = vec![
+            commit("bbb", "Update docs"),
+            commit("aaa", "[Fix] crash on start"),
+        ];
+        let grouping =
+            Grouping::Pattern(Regex::new(r"^\[(?P<section>\w+)\] (?P<description>.+)$").unwrap());
+        let release = Release::from_commits("1.0.1".to_string(), None, &commits, &grouping);
+        assert_eq!(
+            release.to_markdown(),
+            "## v1.0.1\n\n### Fix\n\n- crash on start (aaa)\n\n### Other\n\n- Update docs (bbb)\n"
+        );
+    }
+
+    #[test]
+    fn find_release_roundtrip() {
+        let changelog = update("", &release());
+        let found = find_release(&changelog, "1.1.0").unwrap();
+        assert_eq!(found.date, Some("2026-10-18".to_string()));
+        assert_eq!(found.to_markdown(), release().to_markdown());
+    }
+
+    #[test]
+    fn find_release_hand_written() {
+        let changelog = "# Changelog\n\n## [1.0.1]\n\nSmall fixes.\n\n### Fixed\n\n* a\n  continued\n\n## [1.0.0]\n\n- first\n";
+        let found = find_release(changelog, "1.0.1").unwrap();
+        assert_eq!(found.date, None);
+        assert_eq!(found.sections.len(), 2);
+        assert_eq!(found.sections[0].title, "");
+        assert_eq!(found.sections[0].entries, vec!["Small fixes."]);
+        assert_eq!(found.sections[1].entries, vec!["a continued"]);
+        assert!(find_release(changelog, "1.0").is_none());
+    }
+
+    #[test]
+    fn update_empty_file() {
+        assert_eq!(
+            update("", &release()),
+            format!("{}\n{}", PREAMBLE, release().to_markdown())
+        );
+    }
+
+    #[test]
+    fn update_inserts_above_previous_release() {
+        let changelog = "# Changelog\n\nIntro.\n\n## v1.0.0 - 2026-01-01\n\n- first\n";
+        assert_eq!(
+            update(changelog, &release()),
+            format!(
+                "# Changelog\n\nIntro.\n\n{}\n## v1.0.0 - 2026-01-01\n\n- first\n",
+                release().to_markdown()
+            )
+        );
+    }
+
+    #[test]
+    fn update_promotes_unreleased() {
+        let changelog = "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- by hand\n\n## v1.0.0\n";
+        assert_eq!(
+            update(changelog, &release()),
+            "# Changelog\n\n## v1.1.0 - 2026-10-18\n\n### Added\n\n- by hand\n\n## v1.0.0\n"
+        );
+    }
+
+    #[test]
+    fn update_fills_empty_unreleased() {
+        let changelog = "# Changelog\n\n## Unreleased\n\n## v1.0.0\n";
+        assert_eq!(
+            update(changelog, &release()),
+            format!("# Changelog\n\n{}\n## v1.0.0\n", release().to_markdown())
+        );
+    }
+}
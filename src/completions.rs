@@ -0,0 +1,57 @@
+use clap::{App, Shell};
+
+/// The completion script for `shell` generated from `app`, with `levels` offered in place of
+/// the version. clap 2 only completes the values of a positional argument for zsh, so the
+/// other scripts get them added at the top level of `bin`.
+pub fn script(mut app: App, bin: &str, shell: Shell, levels: &[&str]) -> Result<String, String> {
+    let mut script = Vec::new();
+    app.gen_completions_to(bin, shell, &mut script);
+    let script = String::from_utf8(script).map_err(|e| e.to_string())?;
+    Ok(match shell {
+        Shell::Zsh => script,
+        Shell::Bash => {
+            let root = format!("        {})\n            opts=\"", bin);
+            match script.find(&root) {
+                Some(i) => {
+                    let start = i + root.len();
+                    let end = start + script[start..].find('"').unwrap_or(0);
+                    let opts = script[start..end].replacen("<version>", &levels.join(" "), 1);
+                    format!("{}{}{}", &script[..start], opts, &script[end..])
+                }
+                None => script,
+            }
+        }
+        Shell::Fish => format!(
+            "{}complete -c {} -n \"__fish_use_subcommand\" -a \"{}\" -d 'version level'\n",
+            script,
+            bin,
+            levels.join(" ")
+        ),
+        Shell::Elvish => insert_after(&script, &format!("&'{}'= {{\n", bin), levels, |level| {
+            format!("            cand {} 'version level'\n", level)
+        }),
+        Shell::PowerShell => insert_after(&script, &format!("'{}' {{\n", bin), levels, |level| {
+            format!(
+                "            [CompletionResult]::new('{0}', '{0}', [CompletionResultType]::ParameterValue, 'version level')\n",
+                level
+            )
+        }),
+    })
+}
+
+/// `script` with a line for each of `levels` inserted after the first `anchor`.
+fn insert_after(
+    script: &str,
+    anchor: &str,
+    levels: &[&str],
+    line: impl Fn(&str) -> String,
+) -> String {
+    match script.find(anchor) {
+        Some(i) => {
+            let at = i + anchor.len();
+            let lines: String = levels.iter().map(|level| line(level)).collect();
+            format!("{}{}{}", &script[..at], lines, &script[at..])
+        }
+        None => script.to_string(),
+    }
+}
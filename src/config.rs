@@ -0,0 +1,661 @@
+use std::path::{Path, PathBuf};
+
+use clap::ArgMatches;
+use serde::{Deserialize, Serialize};
+
+use crate::hooks::Stage;
+use crate::webext::Validation;
+
+pub const CONFIG_FILE: &str = ".manifest-bump.toml";
+/// Key under which the configuration can be embedded in `package.json` instead.
+pub const PACKAGE_JSON_KEY: &str = "manifest-bump";
+
+/// Workspace-only options, which are rejected without a `[workspace]` section.
+const WORKSPACE_OPTIONS: [&str; 3] = ["package", "commit-per-package", "changed"];
+
+/// Git-only options, which are rejected when git is not enabled.
+const GIT_OPTIONS: [&str; 7] = [
+    "signature",
+    "message",
+    "push",
+    "rollback-on-failure",
+    "pre-commit",
+    "post-tag",
+    "stage-hook-changes",
+];
+
+/// Options with effects beyond printing the bumped file, which are rejected with `-f -`.
+const STDIN_REJECTED: [&str; 11] = [
+    "git",
+    "interactive",
+    "json",
+    "from-tag",
+    "changelog",
+    "pack",
+    "include",
+    "exclude",
+    "package",
+    "commit-per-package",
+    "changed",
+];
+
+/// A file to bump and the dotted key path of its version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Target {
+    pub path: String,
+    #[serde(default = "default_key")]
+    pub key: String,
+    /// Integer build numbers stored next to the version, e.g. Android `versionCode`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub build: Vec<Build>,
+}
+
+impl Target {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            key: default_key(),
+            build: Vec::new(),
+        }
+    }
+}
+
+/// A secondary integer field that must grow with every release.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Build {
+    /// Dotted key path of the build number.
+    pub key: String,
+    #[serde(default)]
+    pub strategy: BuildStrategy,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BuildStrategy {
+    /// Add one on every bump.
+    #[default]
+    Increment,
+    /// `major * 10000 + minor * 100 + patch` of the new version.
+    Semver,
+}
+
+fn default_key() -> String {
+    "version".to_string()
+}
+
+fn default_files() -> Vec<Target> {
+    vec![Target::new("manifest.json")]
+}
+
+/// A monorepo of independently versioned packages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Workspace {
+    /// Globs (`*` only) for the package directories, e.g. `packages/*`.
+    pub packages: Vec<String>,
+    /// The files to bump inside each package directory.
+    #[serde(default = "default_files")]
+    pub files: Vec<Target>,
+    /// Make one commit per package instead of a single commit for all of them.
+    #[serde(default)]
+    pub commit_per_package: bool,
+}
+
+/// How the extension is zipped: globs relative to the manifest's directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Pack {
+    /// Only pack files matching one of these (everything when empty).
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Where the zip goes, relative to the manifest's directory.
+    #[serde(default = "default_out_dir")]
+    pub out_dir: String,
+    /// Pack after every bump, once the hooks have run.
+    #[serde(default)]
+    pub on_bump: bool,
+}
+impl Default for Pack {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            out_dir: default_out_dir(),
+            on_bump: false,
+        }
+    }
+}
+
+fn default_out_dir() -> String {
+    "dist".to_string()
+}
+
+/// Update manifests for self-hosted extensions, pointed at the new version on every bump.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Updates {
+    /// Download URL of the new version, with the `{new}`, `{tag}`, `{zip}`, … placeholders.
+    pub url: Option<String>,
+    /// Firefox `updates.json` to append the new version to.
+    pub firefox: Option<String>,
+    /// Add-on ID in `updates.json` [default: `browser_specific_settings.gecko.id`].
+    pub firefox_id: Option<String>,
+    /// Chrome `update.xml` to point at the new version.
+    pub chrome: Option<String>,
+    pub chrome_id: Option<String>,
+    /// Add the SHA-256 of the packed zip to the entries.
+    #[serde(default)]
+    pub hash: bool,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Hooks {
+    #[serde(default)]
+    pub pre_bump: Vec<String>,
+    #[serde(default)]
+    pub post_bump: Vec<String>,
+    #[serde(default)]
+    pub pre_commit: Vec<String>,
+    #[serde(default)]
+    pub post_tag: Vec<String>,
+}
+impl Hooks {
+    pub fn get(&self, stage: Stage) -> &[String] {
+        match stage {
+            Stage::PreBump => &self.pre_bump,
+            Stage::PostBump => &self.post_bump,
+            Stage::PreCommit => &self.pre_commit,
+            Stage::PostTag => &self.post_tag,
+        }
+    }
+
+    fn get_mut(&mut self, stage: Stage) -> &mut Vec<String> {
+        match stage {
+            Stage::PreBump => &mut self.pre_bump,
+            Stage::PostBump => &mut self.post_bump,
+            Stage::PreCommit => &mut self.pre_commit,
+            Stage::PostTag => &mut self.post_tag,
+        }
+    }
+}
+
+/// The configuration file as written; anything left out falls back to the defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    pub files: Option<Vec<Target>>,
+    pub variants: Option<Vec<String>>,
+    pub level: Option<String>,
+    pub preid: Option<String>,
+    pub chrome: Option<bool>,
+    pub validate: Option<Validation>,
+    pub calver: Option<String>,
+    pub tag: Option<String>,
+    pub message: Option<String>,
+    pub git: Option<bool>,
+    pub sign: Option<bool>,
+    pub push: Option<String>,
+    pub rollback_on_failure: Option<bool>,
+    pub changelog: Option<String>,
+    pub changelog_pattern: Option<String>,
+    pub stage_hook_changes: Option<bool>,
+    pub allowed_branches: Option<Vec<String>>,
+    pub workspace: Option<Workspace>,
+    #[serde(default)]
+    pub hooks: Hooks,
+    #[serde(default)]
+    pub pack: Pack,
+    pub updates: Option<Updates>,
+}
+impl Config {
+    /// Find the configuration for the current directory: the nearest `.manifest-bump.toml`
+    /// or `package.json` with a `manifest-bump` key, walking up towards the root. Paths in
+    /// the returned config are rewritten to be relative to the current directory.
+    pub fn discover() -> Result<(Self, Option<PathBuf>), String> {
+        let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+        let mut dir = PathBuf::new();
+        for ancestor in cwd.ancestors() {
+            if let Some((config, source)) = Self::load_from(&dir)? {
+                return Ok((config, Some(source)));
+            }
+            if ancestor.parent().is_some() {
+                dir.push("..");
+            }
+        }
+        Ok((Self::default(), None))
+    }
+
+    fn load_from(dir: &Path) -> Result<Option<(Self, PathBuf)>, String> {
+        let file = dir.join(CONFIG_FILE);
+        if file.is_file() {
+            let text = std::fs::read_to_string(&file).map_err(|e| e.to_string())?;
+            let config: Self =
+                toml::from_str(&text).map_err(|e| format!("{}: {}", file.display(), e))?;
+            return Ok(Some((config.relative_to(dir), file)));
+        }
+        let package = dir.join("package.json");
+        if package.is_file() {
+            let text = std::fs::read_to_string(&package).map_err(|e| e.to_string())?;
+            let value: serde_json::Value =
+                serde_json::from_str(&text).map_err(|e| format!("{}: {}", package.display(), e))?;
+            if let Some(embedded) = value.get(PACKAGE_JSON_KEY) {
+                let config: Self = serde_json::from_value(embedded.clone())
+                    .map_err(|e| format!("{}: {}", package.display(), e))?;
+                return Ok(Some((config.relative_to(dir), package)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn relative_to(mut self, dir: &Path) -> Self {
+        let join = |path: &str| dir.join(path).to_string_lossy().into_owned();
+        if let Some(files) = &mut self.files {
+            for target in files {
+                target.path = join(&target.path);
+            }
+        }
+        if let Some(variants) = &mut self.variants {
+            for variant in variants {
+                *variant = join(variant);
+            }
+        }
+        if let Some(workspace) = &mut self.workspace {
+            for pattern in &mut workspace.packages {
+                *pattern = join(pattern);
+            }
+        }
+        if let Some(updates) = &mut self.updates {
+            updates.firefox = updates.firefox.as_deref().map(join);
+            updates.chrome = updates.chrome.as_deref().map(join);
+        }
+        self.changelog = self.changelog.as_deref().map(join);
+        self
+    }
+}
+
+/// The effective settings for a run: the configuration file with CLI flags applied on top.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Settings {
+    pub level: String,
+    /// Identifier for the `pre*` levels, e.g. `rc` in `1.3.0-rc.0`.
+    pub preid: String,
+    /// Enforce Chrome's extension version rules.
+    pub chrome: bool,
+    /// Check extension manifests before writing them.
+    pub validate: Validation,
+    /// CalVer format such as `YYYY.MM.MICRO`; SemVer when unset.
+    pub calver: Option<String>,
+    pub tag: String,
+    pub message: String,
+    pub git: bool,
+    pub sign: bool,
+    pub push: Option<String>,
+    pub rollback_on_failure: bool,
+    pub changelog: Option<String>,
+    pub changelog_pattern: Option<String>,
+    pub stage_hook_changes: bool,
+    pub allowed_branches: Vec<String>,
+    /// Browser-specific manifests (`manifest.chrome.json`, …) that must share one version.
+    pub variants: Vec<String>,
+    pub files: Vec<Target>,
+    pub workspace: Option<Workspace>,
+    pub hooks: Hooks,
+    pub pack: Pack,
+    pub updates: Option<Updates>,
+    /// Where to write the bumped file instead of over the original.
+    #[serde(skip)]
+    pub output: Option<String>,
+    /// Accept an explicit version lower than the current one.
+    #[serde(skip)]
+    pub allow_downgrade: bool,
+    /// Accept an explicit version equal to the current one.
+    #[serde(skip)]
+    pub allow_same: bool,
+}
+impl Settings {
+    pub fn resolve(config: Config, matches: &ArgMatches) -> Result<Self, String> {
+        let variants: Vec<String> = match matches.values_of("variant") {
+            Some(values) => values.map(String::from).collect(),
+            None => config.variants.unwrap_or_default(),
+        };
+        let mut files = match (matches.value_of("file-path"), config.files) {
+            (Some(path), _) => vec![Target::new(path)],
+            (None, Some(files)) => files,
+            (None, None) if !variants.is_empty() => Vec::new(),
+            (None, None) => default_files(),
+        };
+        for variant in &variants {
+            if !files.iter().any(|target| target.path == *variant) {
+                files.push(Target::new(variant));
+            }
+        }
+        if files.is_empty() {
+            return Err("`files` must list at least one file".to_string());
+        }
+        let stdin = files[0].path == "-";
+        if stdin {
+            if let Some(option) = STDIN_REJECTED.iter().find(|o| matches.is_present(o)) {
+                return Err(format!("--{} cannot be used with -f -", option));
+            }
+        }
+
+        let mut hooks = config.hooks;
+        for &stage in &[
+            Stage::PreBump,
+            Stage::PostBump,
+            Stage::PreCommit,
+            Stage::PostTag,
+        ] {
+            let mut commands: Vec<String> = matches
+                .values_of(stage.name())
+                .map(|values| values.map(String::from).collect())
+                .unwrap_or_default();
+            if stage == Stage::PostBump {
+                commands.extend(matches.value_of("after-run").map(String::from));
+            }
+            if !commands.is_empty() {
+                *hooks.get_mut(stage) = commands;
+            }
+        }
+
+        let mut pack = config.pack;
+        pack.on_bump = (pack.on_bump || matches.is_present("pack")) && !stdin;
+        for (name, globs) in [
+            ("include", &mut pack.include),
+            ("exclude", &mut pack.exclude),
+        ] {
+            if let Some(values) = matches.values_of(name) {
+                *globs = values.map(String::from).collect();
+            }
+        }
+
+        // Reading stdin is a pure filter: the configured side effects are left out.
+        let updates = config.updates.filter(|_| !stdin);
+        if let Some(updates) = &updates {
+            if updates.url.is_none() && (updates.firefox.is_some() || updates.chrome.is_some()) {
+                return Err("`updates.url` is required".to_string());
+            }
+            if updates.chrome.is_some() && updates.chrome_id.is_none() {
+                return Err("`updates.chrome-id` is required for update.xml".to_string());
+            }
+            if updates.hash && !pack.on_bump {
+                return Err(
+                    "`updates.hash` needs the zip: set `pack.on-bump` or pass --pack".to_string(),
+                );
+            }
+        }
+
+        let output = matches.value_of("output").map(String::from);
+        if output.is_some() && files.len() != 1 {
+            return Err("--output needs a single file to bump".to_string());
+        }
+
+        let git = !stdin
+            && !matches.is_present("no-git")
+            && (matches.is_present("git") || config.git.unwrap_or(false));
+        if !git {
+            if let Some(option) = GIT_OPTIONS.iter().find(|o| matches.is_present(o)) {
+                return Err(format!("--{} requires --git", option));
+            }
+        }
+        if git && output.is_some() {
+            return Err(
+                "--output cannot be used with git, as the commit would not have the new version (pass --no-git)"
+                    .to_string(),
+            );
+        }
+
+        let push = if matches.is_present("push") {
+            Some(matches.value_of("push").unwrap_or("origin").to_string())
+        } else {
+            config.push
+        };
+        if push.is_none() && matches.is_present("rollback-on-failure") {
+            return Err("--rollback-on-failure requires --push".to_string());
+        }
+
+        let mut workspace = config.workspace.filter(|_| !stdin);
+        match &mut workspace {
+            Some(workspace) => {
+                workspace.commit_per_package |= matches.is_present("commit-per-package");
+            }
+            None => {
+                if let Some(option) = WORKSPACE_OPTIONS.iter().find(|o| matches.is_present(o)) {
+                    return Err(format!("--{} requires a [workspace] in the config", option));
+                }
+            }
+        }
+        let (default_tag, default_message) = if workspace.is_some() {
+            ("{package}@{new}", "📚 release {tags}")
+        } else {
+            ("v{new}", "📚 bump version v{new}")
+        };
+
+        Ok(Self {
+            level: config.level.unwrap_or_else(|| "patch".to_string()),
+            preid: matches
+                .value_of("preid")
+                .map(String::from)
+                .or(config.preid)
+                .unwrap_or_else(|| "rc".to_string()),
+            chrome: matches.is_present("chrome") || config.chrome.unwrap_or(false),
+            validate: if matches.is_present("validate") {
+                Validation::parse(matches.value_of("validate").unwrap_or("error"))?
+            } else {
+                config.validate.unwrap_or_default()
+            },
+            calver: matches
+                .value_of("calver")
+                .map(String::from)
+                .or(config.calver),
+            tag: config.tag.unwrap_or_else(|| default_tag.to_string()),
+            message: matches
+                .value_of("message")
+                .map(String::from)
+                .or(config.message)
+                .unwrap_or_else(|| default_message.to_string()),
+            git,
+            sign: matches.is_present("signature") || config.sign.unwrap_or(false),
+            push,
+            rollback_on_failure: matches.is_present("rollback-on-failure")
+                || config.rollback_on_failure.unwrap_or(false),
+            changelog: if stdin {
+                None
+            } else if matches.is_present("changelog") {
+                Some(
+                    matches
+                        .value_of("changelog")
+                        .map(String::from)
+                        .or(config.changelog)
+                        .unwrap_or_else(|| "CHANGELOG.md".to_string()),
+                )
+            } else {
+                config.changelog
+            },
+            changelog_pattern: matches
+                .value_of("changelog-pattern")
+                .map(String::from)
+                .or(config.changelog_pattern),
+            stage_hook_changes: matches.is_present("stage-hook-changes")
+                || config.stage_hook_changes.unwrap_or(false),
+            allowed_branches: config.allowed_branches.unwrap_or_default(),
+            variants,
+            files,
+            workspace,
+            hooks,
+            pack,
+            updates,
+            output,
+            allow_downgrade: matches.is_present("allow-downgrade"),
+            allow_same: matches.is_present("allow-same"),
+        })
+    }
+
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|e| e.to_string())
+    }
+
+    /// The tag template with `{package}` filled in (left empty outside a workspace).
+    fn tag_template(&self, package: Option<&str>) -> String {
+        self.tag
+            .replace("{package}", package.unwrap_or_default())
+            .replace("{version}", "{new}")
+    }
+
+    /// The tag name for `version` of `package`, from the `tag` template.
+    pub fn tag_for(&self, package: Option<&str>, version: &str) -> String {
+        self.tag_template(package).replace("{new}", version)
+    }
+
+    /// A glob matching every tag of `package` produced by the `tag` template.
+    pub fn tag_glob(&self, package: Option<&str>) -> String {
+        self.tag_for(package, "*")
+    }
+
+    /// The version part of a tag of `package` produced by the `tag` template.
+    pub fn version_of_tag<'t>(&self, package: Option<&str>, tag: &'t str) -> Option<&'t str> {
+        let template = self.tag_template(package);
+        let i = template.find("{new}")?;
+        tag.strip_prefix(&template[..i])?
+            .strip_suffix(&template[i + "{new}".len()..])
+    }
+
+    /// Fail unless the current branch matches one of `allowed-branches` (any branch when empty).
+    pub fn check_branch(&self, branch: &str) -> Result<(), String> {
+        if self.allowed_branches.is_empty()
+            || self.allowed_branches.iter().any(|p| glob_match(p, branch))
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "branch `{}` is not in allowed-branches ({})",
+                branch,
+                self.allowed_branches.join(", ")
+            ))
+        }
+    }
+}
+
+/// Match `text` against a pattern where `*` stands for any run of characters.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let regex = pattern
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join(".*");
+    regex::Regex::new(&format!("^{}$", regex))
+        .map(|r| r.is_match(text))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(tag: &str) -> Settings {
+        Settings {
+            level: "patch".to_string(),
+            preid: "rc".to_string(),
+            chrome: false,
+            validate: Validation::Off,
+            calver: None,
+            tag: tag.to_string(),
+            message: String::new(),
+            git: false,
+            sign: false,
+            push: None,
+            rollback_on_failure: false,
+            changelog: None,
+            changelog_pattern: None,
+            stage_hook_changes: false,
+            allowed_branches: vec!["main".to_string(), "release/*".to_string()],
+            variants: Vec::new(),
+            files: Vec::new(),
+            workspace: None,
+            hooks: Hooks::default(),
+            pack: Pack::default(),
+            updates: None,
+            output: None,
+            allow_downgrade: false,
+            allow_same: false,
+        }
+    }
+
+    #[test]
+    fn config_from_toml() {
+        let config: Config = toml::from_str(
+            r#"
+            tag = "release-{new}"
+            git = true
+            allowed-branches = ["main"]
+
+            [[files]]
+            path = "manifest.json"
+
+            [[files]]
+            path = "app.json"
+            key = "expo.version"
+
+            [hooks]
+            pre-bump = ["npm test"]
+            "#,
+        )
+        .unwrap();
+        let config = config.relative_to(Path::new("app"));
+        let files = config.files.unwrap();
+        assert_eq!(
+            files[0].path,
+            Path::new("app/manifest.json").to_string_lossy()
+        );
+        assert_eq!(files[0].key, "version");
+        assert_eq!(files[1].key, "expo.version");
+        assert_eq!(config.hooks.pre_bump, vec!["npm test"]);
+        assert_eq!(config.git, Some(true));
+    }
+
+    #[test]
+    fn config_rejects_unknown_keys() {
+        assert!(toml::from_str::<Config>("tags = \"v{new}\"").is_err());
+    }
+
+    #[test]
+    fn tag_template() {
+        let settings = settings("release-{new}-final");
+        assert_eq!(settings.tag_for(None, "1.2.3"), "release-1.2.3-final");
+        assert_eq!(settings.tag_glob(None), "release-*-final");
+        assert_eq!(
+            settings.version_of_tag(None, "release-1.2.3-final"),
+            Some("1.2.3")
+        );
+        assert_eq!(settings.version_of_tag(None, "v1.2.3"), None);
+    }
+
+    #[test]
+    fn tag_template_with_package() {
+        let settings = settings("{package}@{new}");
+        assert_eq!(settings.tag_for(Some("popup"), "1.2.3"), "popup@1.2.3");
+        assert_eq!(settings.tag_glob(Some("popup")), "popup@*");
+        assert_eq!(
+            settings.version_of_tag(Some("popup"), "popup@1.2.3"),
+            Some("1.2.3")
+        );
+        assert_eq!(
+            settings.version_of_tag(Some("popup"), "options@1.2.3"),
+            None
+        );
+    }
+
+    #[test]
+    fn allowed_branches() {
+        let settings = settings("v{new}");
+        assert!(settings.check_branch("main").is_ok());
+        assert!(settings.check_branch("release/1.x").is_ok());
+        assert!(settings.check_branch("feature/main").is_err());
+    }
+}
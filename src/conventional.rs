@@ -0,0 +1,124 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::git::Commit;
+
+static HEADER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\w+)(?:\(([^)]*)\))?(!)?: (.+)$").unwrap());
+
+/// The header of a Conventional Commit: `type(scope)!: description`.
+pub struct Header {
+    pub kind: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+impl Header {
+    pub fn parse(subject: &str) -> Option<Self> {
+        let c = HEADER_REGEX.captures(subject.trim())?;
+        Some(Self {
+            kind: c.get(1).unwrap().as_str().to_lowercase(),
+            scope: c.get(2).map(|s| s.as_str().to_string()),
+            breaking: c.get(3).is_some(),
+            description: c.get(4).unwrap().as_str().to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Patch,
+    Minor,
+    Major,
+}
+
+pub fn is_breaking(commit: &Commit) -> bool {
+    Header::parse(&commit.subject).is_some_and(|h| h.breaking)
+        || commit
+            .body
+            .lines()
+            .any(|line| line.starts_with("BREAKING CHANGE") || line.starts_with("BREAKING-CHANGE"))
+}
+
+pub fn level(commit: &Commit) -> Level {
+    if is_breaking(commit) {
+        return Level::Major;
+    }
+    match Header::parse(&commit.subject) {
+        Some(header) if header.kind == "feat" => Level::Minor,
+        _ => Level::Patch,
+    }
+}
+
+/// Pick the highest level among `commits`, together with the commits that
+/// require it. Returns `None` when there is nothing to release.
+pub fn infer(commits: &[Commit]) -> Option<(Level, Vec<&Commit>)> {
+    let max = commits.iter().map(level).max()?;
+    let drivers = commits.iter().filter(|c| level(c) == max).collect();
+    Some((max, drivers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(subject: &str, body: &str) -> Commit {
+        Commit {
+            hash: "0000000".to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn header_simple() {
+        let header = Header::parse("feat: add push").unwrap();
+        assert_eq!(header.kind, "feat");
+        assert_eq!(header.scope, None);
+        assert!(!header.breaking);
+        assert_eq!(header.description, "add push");
+    }
+
+    #[test]
+    fn header_with_scope_and_bang() {
+        let header = Header::parse("fix(parser)!: reject trailing commas").unwrap();
+        assert_eq!(header.kind, "fix");
+        assert_eq!(header.scope, Some("parser".to_string()));
+        assert!(header.breaking);
+    }
+
+    #[test]
+    fn header_not_conventional() {
+        assert!(Header::parse("Update README").is_none());
+    }
+
+    #[test]
+    fn level_of_commits() {
+        assert_eq!(level(&commit("feat: x", "")), Level::Minor);
+        assert_eq!(level(&commit("fix: x", "")), Level::Patch);
+        assert_eq!(level(&commit("docs: x", "")), Level::Patch);
+        assert_eq!(level(&commit("Update README", "")), Level::Patch);
+        assert_eq!(level(&commit("feat!: x", "")), Level::Major);
+        assert_eq!(
+            level(&commit("fix: x", "details\n\nBREAKING CHANGE: removed -x")),
+            Level::Major
+        );
+    }
+
+    #[test]
+    fn infer_picks_highest() {
+        let commits = vec![
+            commit("fix: a", ""),
+            commit("feat: b", ""),
+            commit("feat: c", ""),
+        ];
+        let (level, drivers) = infer(&commits).unwrap();
+        assert_eq!(level, Level::Minor);
+        assert_eq!(drivers.len(), 2);
+    }
+
+    #[test]
+    fn infer_nothing() {
+        assert!(infer(&[]).is_none());
+    }
+}
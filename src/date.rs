@@ -0,0 +1,94 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+impl Date {
+    /// Today's date, or `MANIFEST_BUMP_TODAY` (`YYYY-MM-DD`) when it is set, which pins the
+    /// date for tests.
+    pub fn today() -> Result<Self, String> {
+        if let Ok(today) = std::env::var("MANIFEST_BUMP_TODAY") {
+            return Self::parse(&today).ok_or_else(|| {
+                format!(
+                    "MANIFEST_BUMP_TODAY must be a YYYY-MM-DD date, not `{}`",
+                    today
+                )
+            });
+        }
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Ok(Self::from_days((secs / 86400) as i64))
+    }
+
+    /// Parse a `YYYY-MM-DD` date.
+    fn parse(date: &str) -> Option<Self> {
+        let parts: Vec<&str> = date.split('-').collect();
+        let (year, month, day) = match parts[..] {
+            [year, month, day] => (year.parse().ok()?, month.parse().ok()?, day.parse().ok()?),
+            _ => return None,
+        };
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    /// Convert days since 1970-01-01 into a civil date (proleptic Gregorian calendar).
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Self { year, month, day }
+    }
+}
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_days_epoch() {
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+    }
+
+    #[test]
+    fn from_days_leap_day() {
+        assert_eq!(Date::from_days(19782).to_string(), "2024-02-29");
+    }
+
+    #[test]
+    fn parse_dates() {
+        assert_eq!(
+            Date::parse("2026-10-18"),
+            Some(Date {
+                year: 2026,
+                month: 10,
+                day: 18
+            })
+        );
+        assert_eq!(Date::parse("2026-13-01"), None);
+        assert_eq!(Date::parse("1792324800"), None);
+    }
+
+    #[test]
+    fn from_days_end_of_year() {
+        assert_eq!(Date::from_days(20818).to_string(), "2026-12-31");
+    }
+}
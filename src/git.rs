@@ -1,53 +1,170 @@
-use std::process::Command;
-
-use crate::Version;
-
-fn git_tag(version: &Version) -> Result<(), String> {
-    Command::new("git")
-        .args(&["tag", &format!("v{}", version.to_string())])
-        .status()
-        .map(|_| ())
-        .map_err(|e| e.to_string())
-}
-
-fn git_stage(path: &str) -> Result<(), String> {
-    Command::new("git")
-        .args(&["add", path])
-        .status()
-        .map(|_| ())
-        .map_err(|e| e.to_string())
-}
-
-fn git_commit(
-    version: &Version,
-    is_signature: bool,
-    message: Option<String>,
-    path: &str,
-) -> Result<(), String> {
-    git_stage(path)?;
-    let message = message.unwrap_or_else(|| format!("📚 bump version v{}", version.to_string()));
-    if is_signature {
-        Command::new("git")
-            .args(&["commit", "-m", &message, "-S"])
-            .status()
-            .map(|_| ())
-            .map_err(|e| e.to_string())
+use std::process::{Command, Stdio};
+
+use crate::report;
+
+fn run_git(args: &[&str]) -> Result<(), String> {
+    let mut command = Command::new("git");
+    command.args(args);
+    if report::stdout_reserved() {
+        command.stdout(Stdio::from(std::io::stderr()));
+    }
+    let status = command.status().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
     } else {
-        Command::new("git")
-            .args(&["commit", "-m", &message])
-            .status()
-            .map(|_| ())
-            .map_err(|e| e.to_string())
+        Err(format!("`git {}` failed ({})", args.join(" "), status))
+    }
+}
+
+fn git_output(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        String::from_utf8(output.stdout).map_err(|e| e.to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+pub fn git_tag(tag: &str) -> Result<(), String> {
+    run_git(&["tag", tag])
+}
+
+fn git_stage(paths: &[String]) -> Result<(), String> {
+    let mut args = vec!["add"];
+    args.extend(paths.iter().map(String::as_str));
+    run_git(&args)
+}
+
+/// Stage `paths` and commit them.
+pub fn git_commit(message: &str, is_signature: bool, paths: &[String]) -> Result<(), String> {
+    git_stage(paths)?;
+    let mut args = vec!["commit", "-m", message];
+    if is_signature {
+        args.push("-S");
+    }
+    run_git(&args)
+}
+
+/// Push the current branch and the version tags in a single atomic push,
+/// so the remote either receives all of them or nothing.
+pub fn git_push(remote: &str, tags: &[String]) -> Result<(), String> {
+    let tag_refs: Vec<String> = tags
+        .iter()
+        .map(|tag| format!("refs/tags/{}", tag))
+        .collect();
+    let mut args = vec!["push", "--atomic", remote, "HEAD"];
+    args.extend(tag_refs.iter().map(String::as_str));
+    run_git(&args).map_err(|_| {
+        format!(
+            "push of HEAD and {} to {} was rejected",
+            tag_refs.join(", "),
+            remote
+        )
+    })
+}
+
+/// Undo the bump's commits and tags: delete the local `tags` and move the branch back to
+/// `base`, the commit HEAD was at before the first of them (`None` when the branch had no
+/// commits yet). `--keep` leaves unrelated local changes alone and refuses to clobber them.
+pub fn git_undo_commit_and_tag(tags: &[String], base: Option<&str>) -> Result<(), String> {
+    for tag in tags {
+        run_git(&["tag", "-d", tag])?;
+    }
+    match base {
+        Some(base) => run_git(&["reset", "--keep", base]),
+        None => run_git(&["update-ref", "-d", "HEAD"]),
     }
 }
 
-pub fn git_commit_and_tag(
-    version: &Version,
-    is_signature: bool,
-    message: Option<String>,
-    path: &str,
-) -> Result<(), String> {
-    git_commit(version, is_signature, message, path)?;
-    git_tag(version)?;
-    Ok(())
+/// The full hash of the commit at HEAD.
+pub fn git_head() -> Result<String, String> {
+    git_output(&["rev-parse", "HEAD"]).map(|hash| hash.trim().to_string())
+}
+
+/// The abbreviated hash of the commit at HEAD.
+pub fn git_short_head() -> Result<String, String> {
+    git_output(&["rev-parse", "--short", "HEAD"]).map(|hash| hash.trim().to_string())
+}
+
+pub fn git_current_branch() -> Result<String, String> {
+    git_output(&["rev-parse", "--abbrev-ref", "HEAD"]).map(|branch| branch.trim().to_string())
+}
+
+/// Entries of `git status` as `XY path`, with paths relative to the current directory.
+pub fn git_status() -> Result<Vec<String>, String> {
+    let cdup = git_output(&["rev-parse", "--show-cdup"])?;
+    let status = git_output(&["status", "--porcelain", "--untracked-files=all"])?;
+    Ok(status
+        .lines()
+        .map(|line| {
+            let path = match line.find(" -> ") {
+                Some(i) => &line[i + 4..],
+                None => &line[3..],
+            };
+            format!("{}{}{}", &line[..3], cdup.trim(), path)
+        })
+        .collect())
+}
+
+pub struct Commit {
+    pub hash: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// The most recent tag matching the glob `pattern` reachable from HEAD, if any.
+pub fn git_last_tag(pattern: &str) -> Option<String> {
+    git_last_tag_before("HEAD", pattern)
+}
+
+/// The most recent tag matching the glob `pattern` reachable from `rev`, if any.
+pub fn git_last_tag_before(rev: &str, pattern: &str) -> Option<String> {
+    git_output(&["describe", "--tags", "--abbrev=0", "--match", pattern, rev])
+        .ok()
+        .map(|tag| tag.trim().to_string())
+}
+
+/// Tags matching the glob `pattern` reachable from HEAD.
+pub fn git_merged_tags(pattern: &str) -> Vec<String> {
+    git_output(&["tag", "--list", pattern, "--merged", "HEAD"])
+        .map(|tags| tags.lines().map(|tag| tag.to_string()).collect())
+        .unwrap_or_default()
+}
+
+pub fn git_tag_exists(tag: &str) -> bool {
+    git_output(&["rev-parse", "-q", "--verify", &format!("refs/tags/{}", tag)]).is_ok()
+}
+
+/// The committer date of `rev` as `YYYY-MM-DD`.
+pub fn git_date(rev: &str) -> Result<String, String> {
+    git_output(&["log", "-1", "--format=%cs", rev]).map(|date| date.trim().to_string())
+}
+
+/// Commits reachable from `until` but not from `since` (all commits when `None`), newest
+/// first, optionally limited to those touching `path`.
+pub fn git_log(
+    since: Option<&str>,
+    until: &str,
+    path: Option<&str>,
+) -> Result<Vec<Commit>, String> {
+    let range = since.map_or_else(|| until.to_string(), |tag| format!("{}..{}", tag, until));
+    let mut args = vec!["log", "--format=%h%x1f%s%x1f%b%x1e", &range];
+    if let Some(path) = path {
+        args.extend(["--", path]);
+    }
+    let log = git_output(&args)?;
+    Ok(log
+        .split('\x1e')
+        .filter_map(|entry| {
+            let mut fields = entry.trim_start_matches('\n').splitn(3, '\x1f');
+            Some(Commit {
+                hash: fields.next().filter(|h| !h.is_empty())?.to_string(),
+                subject: fields.next()?.to_string(),
+                body: fields.next().unwrap_or_default().trim().to_string(),
+            })
+        })
+        .collect())
 }
@@ -0,0 +1,201 @@
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+use crate::report;
+
+/// The points in a bump at which hook commands run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// After the new version is computed, before any file is written.
+    PreBump,
+    /// After the manifest (and changelog) are written.
+    PostBump,
+    /// Right before the bump commit is created.
+    PreCommit,
+    /// After the commit is tagged, before pushing.
+    PostTag,
+}
+impl Stage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::PreBump => "pre-bump",
+            Stage::PostBump => "post-bump",
+            Stage::PreCommit => "pre-commit",
+            Stage::PostTag => "post-tag",
+        }
+    }
+}
+
+/// What the bump did, passed to hook commands through `MANIFEST_BUMP_*` environment
+/// variables and `{name}` placeholders in the command string.
+pub struct Context {
+    pub old_version: String,
+    pub new_version: String,
+    pub file: String,
+    pub level: String,
+    pub tag: String,
+    /// The workspace package being bumped, empty outside a workspace.
+    pub package: String,
+}
+impl Context {
+    fn vars(&self) -> [(&'static str, &'static str, &str); 6] {
+        [
+            ("MANIFEST_BUMP_OLD_VERSION", "{old}", &self.old_version),
+            ("MANIFEST_BUMP_NEW_VERSION", "{new}", &self.new_version),
+            ("MANIFEST_BUMP_FILE", "{file}", &self.file),
+            ("MANIFEST_BUMP_LEVEL", "{level}", &self.level),
+            ("MANIFEST_BUMP_TAG", "{tag}", &self.tag),
+            ("MANIFEST_BUMP_PACKAGE", "{package}", &self.package),
+        ]
+    }
+
+    /// Replace the placeholders in `command`; `{version}` is accepted as an alias of `{new}`.
+    pub fn expand(&self, command: &str) -> String {
+        self.substitute(command, |value| value.to_string())
+    }
+
+    /// [`expand`](Self::expand) for a shell command: values are quoted, so a path or package
+    /// with spaces or shell metacharacters stays a single word.
+    pub fn expand_for_shell(&self, command: &str) -> String {
+        self.substitute(command, quote)
+    }
+
+    /// Replace the placeholders in a single scan, so a value that contains a placeholder
+    /// is left as it is.
+    fn substitute(&self, command: &str, value: impl Fn(&str) -> String) -> String {
+        let vars = self.vars();
+        let mut placeholders: Vec<(&str, &str)> = vars.iter().map(|(_, p, v)| (*p, *v)).collect();
+        placeholders.push(("{version}", &self.new_version));
+
+        let mut expanded = String::new();
+        let mut rest = command;
+        while let Some(i) = rest.find('{') {
+            expanded.push_str(&rest[..i]);
+            rest = &rest[i..];
+            match placeholders.iter().find(|(p, _)| rest.starts_with(p)) {
+                Some((placeholder, v)) => {
+                    expanded += &value(v);
+                    rest = &rest[placeholder.len()..];
+                }
+                None => {
+                    expanded.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        expanded + rest
+    }
+}
+
+/// `value` as one word for the shell that runs hooks, left bare when nothing in it is special.
+/// `cmd` has no way to escape `%`, so hooks that must handle any path should read the
+/// `MANIFEST_BUMP_*` variables instead.
+fn quote(value: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "@+=:,./_-".contains(c);
+    if !value.is_empty() && value.chars().all(plain) {
+        value.to_string()
+    } else if cfg!(target_os = "windows") {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+fn shell(command: &str) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    }
+}
+
+/// Run `commands` in order, stopping at the first one that fails or exits non-zero.
+pub fn run_hook(stage: Stage, commands: &[String], context: &Context) -> Result<(), String> {
+    for command in commands {
+        let command = context.expand_for_shell(command);
+        let mut process = shell(&command);
+        for (name, _, value) in context.vars().iter() {
+            process.env(name, value);
+        }
+        if report::stdout_reserved() {
+            process.stdout(Stdio::from(std::io::stderr()));
+        }
+        let start = Instant::now();
+        let status = process.env("MANIFEST_BUMP_STAGE", stage.name()).status();
+        report::record(|report| {
+            report.hooks.push(report::Hook {
+                stage: stage.name(),
+                command: command.clone(),
+                exit_code: status.as_ref().ok().and_then(|status| status.code()),
+                duration_ms: start.elapsed().as_millis() as u64,
+            })
+        });
+        let status = status
+            .map_err(|e| format!("{} hook `{}` could not run: {}", stage.name(), command, e))?;
+        if !status.success() {
+            return Err(format!(
+                "{} hook `{}` failed ({})",
+                stage.name(),
+                command,
+                status
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> Context {
+        Context {
+            old_version: "1.0.0".to_string(),
+            new_version: "1.1.0".to_string(),
+            file: "manifest.json".to_string(),
+            level: "minor".to_string(),
+            tag: "popup@1.1.0".to_string(),
+            package: "popup".to_string(),
+        }
+    }
+
+    #[test]
+    fn expand_placeholders() {
+        assert_eq!(
+            context().expand("zip dist/{package}-{new}.zip # {old} {level} {tag} {file}"),
+            "zip dist/popup-1.1.0.zip # 1.0.0 minor popup@1.1.0 manifest.json"
+        );
+    }
+
+    #[test]
+    fn expand_leaves_unknown_braces() {
+        assert_eq!(context().expand("echo {x} {}"), "echo {x} {}");
+    }
+
+    #[test]
+    fn expand_leaves_placeholders_in_values() {
+        let context = Context {
+            file: "{new}/{package}.json".to_string(),
+            ..context()
+        };
+        assert_eq!(context.expand("{file} {new}"), "{new}/{package}.json 1.1.0");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn expand_for_shell_quotes_values() {
+        let context = Context {
+            file: "my dir/manifest.json".to_string(),
+            package: "it's;rm".to_string(),
+            ..context()
+        };
+        assert_eq!(
+            context.expand_for_shell("cp {file} dist/{package}-{new} # {tag}"),
+            "cp 'my dir/manifest.json' dist/'it'\\''s;rm'-1.1.0 # popup@1.1.0"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use std::io::{BufRead, IsTerminal, Write};
+
+use crate::scheme::Scheme;
+use crate::version::{Query, Version};
+
+/// Print `prompt` to stderr and read one line of answer from `input`.
+fn ask(prompt: &str, input: &mut impl BufRead) -> Result<String, String> {
+    eprint!("{}", prompt);
+    std::io::stderr().flush().map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    if input.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+        eprintln!();
+        return Err(if std::io::stdin().is_terminal() {
+            "no answer given".to_string()
+        } else {
+            "no answer on stdin (not a terminal); pass a version or level instead of --interactive"
+                .to_string()
+        });
+    }
+    Ok(line.trim().to_string())
+}
+
+/// Show the candidate next versions of `current` and read the choice from `input`: a menu
+/// number, a level name or an explicit version. Returns the level or version to bump to.
+pub fn choose_version(
+    package: Option<&str>,
+    current: &Version,
+    preid: &str,
+    scheme: &dyn Scheme,
+    input: &mut impl BufRead,
+) -> Result<String, String> {
+    let levels = scheme.levels();
+    match package {
+        Some(package) => eprintln!("{}: current version {}", package, current),
+        None => eprintln!("current version {}", current),
+    }
+    for (i, level) in levels.iter().enumerate() {
+        match scheme.bump(current, Query::parse(level, preid)?) {
+            Ok(candidate) => eprintln!("  {}) {:<10}  {}", i + 1, level, candidate),
+            Err(e) => eprintln!("  {}) {:<10}  ({})", i + 1, level, e),
+        }
+    }
+    let custom = levels.len() + 1;
+    eprintln!("  {}) custom", custom);
+
+    loop {
+        let answer = ask(&format!("next version [1-{}]: ", custom), input)?;
+        let level = match answer.parse::<usize>() {
+            Ok(n) if (1..custom).contains(&n) => levels[n - 1].to_string(),
+            Ok(n) if n == custom => loop {
+                let version = ask("version: ", input)?;
+                match Version::from_str(version.trim_start_matches('v')) {
+                    Ok(version) => break version.to_string(),
+                    Err(e) => eprintln!("{}", e),
+                }
+            },
+            _ if levels.contains(&answer.as_str()) => answer,
+            _ => match Version::from_str(answer.trim_start_matches('v')) {
+                Ok(version) => version.to_string(),
+                Err(_) => {
+                    eprintln!("pick 1-{}, a level or a version", custom);
+                    continue;
+                }
+            },
+        };
+        return Ok(level);
+    }
+}
+
+/// Ask a yes/no `question`; anything but `y`/`yes` is a no.
+pub fn confirm(question: &str, input: &mut impl BufRead) -> Result<bool, String> {
+    let answer = ask(&format!("{} [y/N] ", question), input)?;
+    Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::SemVer;
+
+    fn choose(answers: &str) -> Result<String, String> {
+        let current = Version::from_str("1.2.3").unwrap();
+        choose_version(None, &current, "rc", &SemVer, &mut answers.as_bytes())
+    }
+
+    #[test]
+    fn choose_from_menu() {
+        assert_eq!(choose("2\n").unwrap(), "minor");
+        assert_eq!(choose("prerelease\n").unwrap(), "prerelease");
+        assert_eq!(choose("8\nnope\nv1.5.0\n").unwrap(), "1.5.0");
+        assert_eq!(choose("42\n1.4.0\n").unwrap(), "1.4.0");
+        assert!(choose("").is_err());
+    }
+
+    #[test]
+    fn confirm_defaults_to_no() {
+        assert!(confirm("apply?", &mut "y\n".as_bytes()).unwrap());
+        assert!(!confirm("apply?", &mut "\n".as_bytes()).unwrap());
+        assert!(confirm("apply?", &mut "".as_bytes()).is_err());
+    }
+}
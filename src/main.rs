@@ -1,174 +1,1551 @@
+mod changelog;
+mod completions;
+mod config;
+mod conventional;
+mod date;
 mod git;
+mod hooks;
+mod interactive;
+mod man;
+mod notes;
+mod output;
+mod pack;
 mod parser;
+mod range;
+mod report;
+mod scheme;
+mod updates;
+mod version;
+mod webext;
+mod workspace;
 
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
-use clap::{crate_authors, crate_description, crate_name, crate_version, App, Arg};
-use once_cell::sync::Lazy;
+use crate::config::{Config, Settings};
+use crate::parser::parse_json_with_builds;
+use crate::version::{Query, Version};
+use clap::{
+    crate_authors, crate_description, crate_name, crate_version, App, AppSettings, Arg, ArgMatches,
+    Shell, SubCommand,
+};
 
-use crate::parser::parse_json;
+/// Values offered by shell completions for the `version` argument and `--package`. They are
+/// only set on the app completions are generated from, since the app itself also accepts
+/// explicit versions and checks package names against the workspace.
+#[derive(Default)]
+struct Completions<'b> {
+    levels: &'b [&'b str],
+    packages: &'b [&'b str],
+}
 
-fn create_app<'a, 'b>() -> App<'a, 'b> {
+fn create_app<'a, 'b>(completions: &Completions<'b>) -> App<'a, 'b> {
+    let mut version = Arg::with_name("version").index(1).validator(|s: String| {
+        if is_version_query(&s) {
+            Ok(())
+        } else {
+            Err("Invalid version format".to_string())
+        }
+    });
+    if !completions.levels.is_empty() {
+        version = version.possible_values(completions.levels);
+    }
+    let mut package = Arg::with_name("package")
+        .short("p")
+        .long("package")
+        .help("only bump the named workspace package")
+        .value_name("name")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1);
+    if !completions.packages.is_empty() {
+        package = package.possible_values(completions.packages);
+    }
     let app = App::new(crate_name!())
         .version(crate_version!())
         .author(crate_authors!())
         .about(crate_description!())
-        .arg(Arg::with_name("version").index(1).validator(|s: String| {
-            if ["minor", "major", "patch"].contains(&s.as_str()) {
-                Ok(())
-            } else {
-                if VERSION_REGEX.is_match(&s) {
-                    Ok(())
-                } else {
-                    Err("Invalid version format".to_string())
-                }
-            }
-        }))
+        .arg(version)
         .arg(
             Arg::with_name("git")
                 .short("g")
                 .long("git")
                 .help("git commit and add tag"),
         )
+        .arg(
+            Arg::with_name("no-git")
+                .long("no-git")
+                .help("do not commit or tag, even if the config enables git")
+                .conflicts_with("git"),
+        )
         .arg(
             Arg::with_name("signature")
                 .short("S")
-                .help("signature for git commit")
-                .requires("git"),
+                .help("signature for git commit"),
         )
         .arg(
             Arg::with_name("message")
                 .short("m")
                 .long("message")
-                .help("message for git commit")
-                .requires("git")
+                .help("message for git commit, {new} is replaced with the new version")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("push")
+                .long("push")
+                .help("push the commit and tag to <remote> [default: origin]")
+                .value_name("remote")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true),
+        )
+        .arg(
+            Arg::with_name("validate")
+                .long("validate")
+                .help("check extension manifests before writing them [default: error]")
+                .value_name("mode")
+                .takes_value(true)
+                .possible_values(&["off", "warn", "error"])
+                .min_values(0)
+                .require_equals(true),
+        )
+        .arg(
+            Arg::with_name("rollback-on-failure")
+                .long("rollback-on-failure")
+                .help("delete the tag and reset the commit if the push is rejected"),
+        )
+        .arg(
+            Arg::with_name("changelog")
+                .long("changelog")
+                .help("add a section for the new version to <changelog> [default: CHANGELOG.md]")
+                .value_name("changelog")
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true),
+        )
+        .arg(
+            Arg::with_name("changelog-pattern")
+                .long("changelog-pattern")
+                .help("group changelog entries by the `section` group of <regex> instead of Conventional Commit types")
+                .value_name("regex")
+                .takes_value(true)
+                .validator(|s: String| regex::Regex::new(&s).map(|_| ()).map_err(|e| e.to_string())),
+        )
         .arg(
             Arg::with_name("after-run")
                 .short("r")
                 .long("run")
-                .help("run after version bump (before commit)")
+                .help("run after version bump (before commit), same as --post-bump")
                 .takes_value(true),
         )
+        .arg(hook_arg(hooks::Stage::PreBump, "run before any file is written"))
+        .arg(hook_arg(hooks::Stage::PostBump, "run after the new version is written"))
+        .arg(hook_arg(hooks::Stage::PreCommit, "run before the bump commit"))
+        .arg(hook_arg(hooks::Stage::PostTag, "run after the tag is created"))
+        .arg(
+            Arg::with_name("stage-hook-changes")
+                .long("stage-hook-changes")
+                .help("include files changed by post-bump and pre-commit hooks in the commit"),
+        )
         .arg(
             Arg::with_name("file-path")
                 .short("f")
                 .long("file")
-                .help("file path to version.json")
+                .help("file path to version.json, - for stdin [default: manifest.json]")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .help("write the bumped file to <path>, leaving the original untouched")
+                .value_name("path")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("variant")
+                .long("variant")
+                .help("browser-specific manifest that must keep the same version")
+                .value_name("file")
                 .takes_value(true)
-                .default_value("manifest.json"),
-        );
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("from-tag")
+                .long("from-tag")
+                .help("bump from the highest version tag reachable from HEAD instead of the file"),
+        )
+        .arg(
+            Arg::with_name("calver")
+                .long("calver")
+                .help("use calendar versioning with this format, e.g. YYYY.MM.MICRO")
+                .value_name("format")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("chrome")
+                .long("chrome")
+                .help("enforce Chrome's version rules (each part at most 65535)"),
+        )
+        .arg(
+            Arg::with_name("allow-downgrade")
+                .long("allow-downgrade")
+                .help("accept an explicit version lower than the current one"),
+        )
+        .arg(
+            Arg::with_name("allow-same")
+                .long("allow-same")
+                .help("accept an explicit version equal to the current one"),
+        )
+        .arg(
+            Arg::with_name("interactive")
+                .short("i")
+                .long("interactive")
+                .help("pick the next version from a menu and confirm before applying")
+                .conflicts_with("version"),
+        )
+        .arg(
+            Arg::with_name("preid")
+                .long("preid")
+                .help("identifier for prerelease versions [default: rc]")
+                .value_name("id")
+                .takes_value(true),
+        )
+        .arg(package)
+        .arg(
+            Arg::with_name("commit-per-package")
+                .long("commit-per-package")
+                .help("make one commit per workspace package instead of a single commit"),
+        )
+        .arg(
+            Arg::with_name("changed")
+                .long("changed")
+                .help("only bump workspace packages with commits since their last tag"),
+        )
+        .arg(
+            Arg::with_name("json")
+                .long("json")
+                .help("print a JSON report of the run on stdout instead of the summary")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("output-env")
+                .long("output-env")
+                .help("write old_version, new_version, tag and changed_files to a dotenv file")
+                .value_name("file")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("pack")
+                .long("pack")
+                .help("zip the extension into dist/ after the bump"),
+        )
+        .arg(pack_glob_arg("include", "only pack files matching this glob"))
+        .arg(pack_glob_arg("exclude", "leave files matching this glob out of the zip"))
+        .subcommands(subcommands());
     app
 }
 
-enum Query {
-    Version(Version),
-    Major,
-    Minor,
-    Patch,
+/// The subcommands of the app, also listed one by one in the man page.
+fn subcommands<'a, 'b>() -> Vec<App<'a, 'b>> {
+    vec![
+        SubCommand::with_name("notes")
+            .about("print the release notes for a version")
+            .arg(
+                Arg::with_name("version")
+                    .index(1)
+                    .help("version to print [default: the version in the manifest]"),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .long("format")
+                    .help("output format")
+                    .takes_value(true)
+                    .possible_values(&["markdown", "json"])
+                    .default_value("markdown"),
+            )
+            .arg(
+                Arg::with_name("changelog")
+                    .long("changelog")
+                    .help("changelog to read the notes from [default: CHANGELOG.md]")
+                    .takes_value(true),
+            )
+            .arg(subcommand_package_arg())
+            .arg(
+                Arg::with_name("file-path")
+                    .short("f")
+                    .long("file")
+                    .help("file path to version.json, - for stdin [default: manifest.json]")
+                    .takes_value(true),
+            ),
+        SubCommand::with_name("check")
+            .about("check whether the version satisfies a range; exits 0 if it does, 1 if not")
+            .arg(
+                Arg::with_name("satisfies")
+                    .long("satisfies")
+                    .help("npm/Cargo-style range, e.g. '>=2.1.0' or '^1.4'")
+                    .value_name("range")
+                    .takes_value(true)
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("version")
+                    .index(1)
+                    .help("version to check [default: the version in the manifest]"),
+            )
+            .arg(subcommand_package_arg())
+            .arg(
+                Arg::with_name("file-path")
+                    .short("f")
+                    .long("file")
+                    .help("file path to version.json, - for stdin [default: manifest.json]")
+                    .takes_value(true),
+            ),
+        SubCommand::with_name("snapshot")
+            .about("print a snapshot prerelease such as 1.3.0-nightly.20261017.abc1234; never commits or tags")
+            .arg(
+                Arg::with_name("level")
+                    .index(1)
+                    .help("release the snapshot leads up to")
+                    .possible_values(&["patch", "minor", "major"])
+                    .default_value("patch"),
+            )
+            .arg(
+                Arg::with_name("preid")
+                    .long("preid")
+                    .help("identifier for the snapshot [default: nightly]")
+                    .value_name("id")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("write")
+                    .long("write")
+                    .help("write the snapshot version to the files instead of only printing it"),
+            )
+            .arg(subcommand_package_arg())
+            .arg(
+                Arg::with_name("file-path")
+                    .short("f")
+                    .long("file")
+                    .help("file path to version.json, - for stdin [default: manifest.json]")
+                    .takes_value(true),
+            ),
+        SubCommand::with_name("pack")
+            .about("zip the extension into dist/{name}-{version}.zip")
+            .arg(pack_glob_arg("include", "only pack files matching this glob"))
+            .arg(pack_glob_arg("exclude", "leave files matching this glob out of the zip"))
+            .arg(subcommand_package_arg())
+            .arg(
+                Arg::with_name("file-path")
+                    .short("f")
+                    .long("file")
+                    .help("file path to version.json, - for stdin [default: manifest.json]")
+                    .takes_value(true),
+            ),
+        SubCommand::with_name("config")
+            .about("inspect the configuration")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(
+                SubCommand::with_name("show")
+                    .about("print the effective settings (config file merged with defaults)"),
+            ),
+        SubCommand::with_name("completions")
+            .about("print a shell completion script, with the levels and packages of the config")
+            .arg(
+                Arg::with_name("shell")
+                    .index(1)
+                    .required(true)
+                    .possible_values(&Shell::variants()),
+            ),
+        SubCommand::with_name("man").about("print the man page in roff"),
+    ]
+}
+
+fn is_version_query(s: &str) -> bool {
+    s == "auto" || version::LEVELS.contains(&s) || Version::is_version(s)
+}
+
+fn hook_arg<'a, 'b>(stage: hooks::Stage, help: &'b str) -> Arg<'a, 'b> {
+    Arg::with_name(stage.name())
+        .long(stage.name())
+        .help(help)
+        .value_name("command")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+}
+
+fn subcommand_package_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("package")
+        .short("p")
+        .long("package")
+        .help("workspace package to read")
+        .value_name("name")
+        .takes_value(true)
+}
+
+fn pack_glob_arg<'a, 'b>(name: &'a str, help: &'b str) -> Arg<'a, 'b> {
+    Arg::with_name(name)
+        .long(name)
+        .help(help)
+        .value_name("glob")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+}
+
+/// The changelog at `path` with the release notes for `version` (from the commits
+/// since the last tag, limited to `dir` when given) added.
+fn update_changelog(
+    path: &Path,
+    version: &Version,
+    pattern: Option<&str>,
+    last_tag: Option<&str>,
+    dir: Option<&str>,
+) -> Result<String, String> {
+    let grouping = match pattern {
+        Some(pattern) => {
+            changelog::Grouping::Pattern(regex::Regex::new(pattern).map_err(|e| e.to_string())?)
+        }
+        None => changelog::Grouping::Conventional,
+    };
+    let commits = git::git_log(last_tag, "HEAD", dir)?;
+    let release = changelog::Release::from_commits(
+        version.to_string(),
+        Some(date::Date::today()?.to_string()),
+        &commits,
+        &grouping,
+    );
+    let current = match std::fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.to_string()),
+    };
+    Ok(changelog::update(&current, &release))
+}
+
+/// Everything the bump changed so far — files with their previous contents, and the
+/// commits and tags created — so that a failure can put the repository back.
+#[derive(Default)]
+struct Rollback {
+    files: Vec<(PathBuf, Option<Vec<u8>>)>,
+    tags: Vec<String>,
+    /// The commit HEAD was at before the first bump commit, once one has been made (`None`
+    /// inside when the branch had no commits).
+    base: Option<Option<String>>,
+}
+impl Rollback {
+    fn write(&mut self, path: &Path, contents: &str) -> Result<(), String> {
+        self.track(path);
+        std::fs::write(path, contents).map_err(|e| e.to_string())
+    }
+
+    /// Remember what is at `path` before something else writes it, such as the packed zip.
+    fn track(&mut self, path: &Path) {
+        if !self.files.iter().any(|(p, _)| p == path) {
+            let original = std::fs::read(path).ok();
+            self.files.push((path.to_path_buf(), original));
+        }
+    }
+
+    /// Record a commit made on top of `parent`.
+    fn committed(&mut self, parent: Option<&str>) {
+        if self.base.is_none() {
+            self.base = Some(parent.map(str::to_string));
+        }
+    }
+
+    fn tagged(&mut self, tag: &str) {
+        self.tags.push(tag.to_string());
+    }
+
+    fn restore(&self) {
+        if let Some(base) = &self.base {
+            git::git_undo_commit_and_tag(&self.tags, base.as_deref())
+                .or_fail("Failed to roll back commit and tag");
+            report::record(|report| {
+                report.commits.clear();
+                report.tags.clear();
+            });
+        }
+        for (path, original) in self.files.iter().rev() {
+            let _ = match original {
+                Some(contents) => std::fs::write(path, contents),
+                None => std::fs::remove_file(path),
+            };
+        }
+    }
+
+    fn abort(&self, error: String) -> ! {
+        self.restore();
+        report::fail(1, error);
+    }
+}
+
+/// Resolve `auto` from the Conventional Commits since `last_tag` (touching `dir`, if given).
+fn auto_query(last_tag: Option<&str>, dir: Option<&str>) -> Result<Query, String> {
+    let commits = git::git_log(last_tag, "HEAD", dir)?;
+    let since = last_tag.unwrap_or("the first commit");
+    let (level, drivers) = conventional::infer(&commits)
+        .ok_or_else(|| format!("No releasable commits since {}", since))?;
+    let (query, name) = match level {
+        conventional::Level::Major => (Query::Major, "major"),
+        conventional::Level::Minor => (Query::Minor, "minor"),
+        conventional::Level::Patch => (Query::Patch, "patch"),
+    };
+    let mut lines = vec![format!("{} bump since {}:", name, since)];
+    lines.extend(
+        drivers
+            .iter()
+            .map(|commit| format!("  {} {}", commit.hash, commit.subject)),
+    );
+    for line in lines {
+        if report::stdout_reserved() {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+    Ok(query)
+}
+
+fn print_notes(matches: &ArgMatches, settings: &Settings) -> Result<(), String> {
+    let (package, targets) = subcommand_targets(matches, settings)?;
+    let version = match matches.value_of("version") {
+        Some(version) => version.trim_start_matches('v').to_string(),
+        None => Document::read(&targets[0])?
+            .parsed_json
+            .get_version()
+            .to_string(),
+    };
+    let changelog = matches
+        .value_of("changelog")
+        .or(settings.changelog.as_deref())
+        .unwrap_or("CHANGELOG.md");
+    let changelog = changelog_path(package.as_ref(), changelog);
+    let (release, source) = notes::release_notes(&version, &changelog, settings, package.as_ref())?;
+    if report::is_json() {
+        report::output(notes::to_json(&release, source));
+    } else if matches.value_of("format") == Some("json") {
+        println!("{}", notes::to_json(&release, source));
+    } else {
+        print!("{}", release.body());
+    }
+    Ok(())
+}
+
+/// Whether the given version, or the one in the manifest, is in the `--satisfies` range.
+fn check(matches: &ArgMatches, settings: &Settings) -> Result<bool, String> {
+    let range = range::Range::parse(matches.value_of("satisfies").unwrap())?;
+    let (_, targets) = subcommand_targets(matches, settings)?;
+    let version = match matches.value_of("version") {
+        Some(version) => Version::from_str(version.trim_start_matches('v'))?,
+        None => Document::read(&targets[0])?
+            .parsed_json
+            .get_version()
+            .clone(),
+    };
+    let satisfied = range.matches(&version);
+    if report::is_json() {
+        report::output(serde_json::json!({
+            "version": version.to_string(),
+            "range": matches.value_of("satisfies").unwrap(),
+            "satisfies": satisfied,
+        }));
+        return Ok(satisfied);
+    }
+    println!(
+        "{} {} {}",
+        version,
+        if satisfied {
+            "satisfies"
+        } else {
+            "does not satisfy"
+        },
+        matches.value_of("satisfies").unwrap()
+    );
+    Ok(satisfied)
+}
+
+/// The snapshot version for the current state: the next `level` release with a prerelease
+/// of the snapshot id, today's date and the short commit hash (left out outside a git
+/// repository), e.g. `1.3.0-nightly.20261017.abc1234`. Written to the files with `--write`.
+fn snapshot(matches: &ArgMatches, settings: &Settings) -> Result<Version, String> {
+    let (package, targets) = subcommand_targets(matches, settings)?;
+    let documents = targets
+        .iter()
+        .map(Document::read)
+        .collect::<Result<Vec<_>, _>>()?;
+    check_variants(settings, &documents)?;
+    let current = documents[0].parsed_json.get_version().clone();
+    let level = Query::parse(matches.value_of("level").unwrap(), &settings.preid)?;
+    let release = scheme::resolve(settings.calver.as_deref())?.bump(&current, level)?;
+
+    let date = date::Date::today()?;
+    let date = format!("{:04}{:02}{:02}", date.year, date.month, date.day);
+    let preid = matches.value_of("preid").unwrap_or("nightly");
+    let mut pre = vec![preid, date.as_str()];
+    // A numeric identifier must not start with a zero, which an all-digit hash might.
+    let hash = git::git_short_head().ok().map(|hash| {
+        if hash.chars().all(|c| c.is_ascii_digit()) {
+            format!("g{}", hash)
+        } else {
+            hash
+        }
+    });
+    pre.extend(hash.as_deref());
+    let version = release.with_prerelease(&pre);
+    Version::from_str(&version.to_string())
+        .map_err(|_| format!("invalid snapshot version {}", version))?;
+
+    if matches.is_present("write") {
+        if targets[0].path == "-" {
+            return Err("--write cannot be used with -f -".to_string());
+        }
+        for document in &documents {
+            document.validate(&version, settings.validate)?;
+        }
+        for document in &documents {
+            std::fs::write(&document.target.path, document.render(&version))
+                .map_err(|e| format!("{}: {}", document.target.path, e))?;
+            report::record(|report| {
+                report.files.push(report::File {
+                    path: document.target.path.clone(),
+                    key: document.target.key.clone(),
+                    package: package.as_ref().map(|p| p.name.clone()),
+                    old_version: document.parsed_json.get_version().to_string(),
+                    new_version: Some(version.to_string()),
+                })
+            });
+        }
+    }
+    Ok(version)
+}
+
+/// The highest semver version tag of `package` reachable from HEAD.
+fn highest_tag_version(settings: &Settings, package: Option<&str>) -> Option<Version> {
+    git::git_merged_tags(&settings.tag_glob(package))
+        .iter()
+        .filter_map(|tag| Version::from_str(settings.version_of_tag(package, tag)?).ok())
+        .max()
+}
+
+/// A target file read from disk, with the version still to be bumped.
+struct Document {
+    target: config::Target,
+    parsed_json: parser::ParsedJson,
+    /// The build numbers to write, one per `target.build`.
+    new_builds: Vec<u64>,
+}
+impl Document {
+    /// Read `target`, from stdin when its path is `-`.
+    fn read(target: &config::Target) -> Result<Self, String> {
+        let json = if target.path == "-" {
+            let mut json = String::new();
+            std::io::stdin()
+                .read_to_string(&mut json)
+                .map_err(|e| format!("stdin: {}", e))?;
+            json
+        } else {
+            std::fs::read_to_string(&target.path).map_err(|e| format!("{}: {}", target.path, e))?
+        };
+        let build_keys: Vec<&str> = target.build.iter().map(|b| b.key.as_str()).collect();
+        let parsed_json = parse_json_with_builds(json, &target.key, &build_keys)
+            .map_err(|e| format!("{}: {}", target.path, e))?;
+        if !parsed_json.has_version() {
+            return Err(format!(
+                "No version found at `{}` in {}",
+                target.key, target.path
+            ));
+        }
+        if let Some(i) = parsed_json.builds.iter().position(|b| b.value.is_none()) {
+            return Err(format!(
+                "No build number found at `{}` in {}",
+                target.build[i].key, target.path
+            ));
+        }
+        Ok(Self {
+            target: target.clone(),
+            parsed_json,
+            new_builds: Vec::new(),
+        })
+    }
+
+    /// Work out the build numbers for `version`; they must keep growing, like the version.
+    fn plan_builds(&mut self, version: &Version, settings: &Settings) -> Result<(), String> {
+        self.new_builds.clear();
+        for (build, current) in self.target.build.iter().zip(&self.parsed_json.builds) {
+            let current = current.value.unwrap();
+            let next = match build.strategy {
+                config::BuildStrategy::Increment => current.checked_add(1),
+                config::BuildStrategy::Semver => {
+                    let (major, minor, patch) = version.release();
+                    if minor >= 100 || patch >= 100 {
+                        return Err(format!(
+                            "{} cannot be encoded as major*10000+minor*100+patch for `{}`",
+                            version, build.key
+                        ));
+                    }
+                    major
+                        .checked_mul(10000)
+                        .and_then(|n| n.checked_add(minor * 100 + patch))
+                }
+            }
+            .ok_or_else(|| format!("build number `{}` overflows", build.key))?;
+            if next < current && !settings.allow_downgrade {
+                return Err(format!(
+                    "build number `{}` in {} would go down from {} to {}",
+                    build.key, self.target.path, current, next
+                ));
+            }
+            // Prereleases share the code of their release, so only the prerelease may change.
+            let same_release = build.strategy == config::BuildStrategy::Semver
+                && version.release() == self.parsed_json.get_version().release();
+            if next == current && !same_release && !settings.allow_same {
+                return Err(format!(
+                    "build number `{}` in {} is already {}",
+                    build.key, self.target.path, current
+                ));
+            }
+            self.new_builds.push(next);
+        }
+        Ok(())
+    }
+
+    /// Check the bumped file if it is an extension manifest, warning about or failing on
+    /// the problems found depending on `validation`.
+    fn validate(&self, version: &Version, validation: webext::Validation) -> Result<(), String> {
+        if validation == webext::Validation::Off || !webext::is_manifest(&self.target.path) {
+            return Ok(());
+        }
+        let problems = webext::validate(&self.render(version));
+        if problems.is_empty() {
+            return Ok(());
+        }
+        if validation == webext::Validation::Warn {
+            for problem in problems {
+                report::warning(format!("{}: {}", self.target.path, problem));
+            }
+            return Ok(());
+        }
+        Err(format!(
+            "{} is not a valid extension manifest:\n  {}",
+            self.target.path,
+            problems.join("\n  ")
+        ))
+    }
+
+    /// The file contents with `version` and the new build numbers written in.
+    fn render(&self, version: &Version) -> String {
+        let mut json = self.parsed_json.clone();
+        *json.get_version_mut() = version.clone();
+        for (build, next) in json.builds.iter_mut().zip(&self.new_builds) {
+            build.value = Some(*next);
+        }
+        json.emb_string()
+    }
+}
+
+/// Fail unless every variant manifest among `documents` is at the same version, so one bump
+/// keeps them in sync.
+fn check_variants(settings: &Settings, documents: &[Document]) -> Result<(), String> {
+    let mut variants = documents
+        .iter()
+        .filter(|document| settings.variants.contains(&document.target.path));
+    let first = match variants.next() {
+        Some(first) => first,
+        None => return Ok(()),
+    };
+    for variant in variants {
+        if variant.parsed_json.get_version() != first.parsed_json.get_version() {
+            return Err(format!(
+                "variants are out of sync: {} is at {} but {} is at {}",
+                first.target.path,
+                first.parsed_json.get_version(),
+                variant.target.path,
+                variant.parsed_json.get_version()
+            ));
+        }
+    }
+    Ok(())
 }
 
-pub struct Version {
-    major: u16,
-    minor: u16,
-    patch: u16,
+/// One independently versioned release: the whole project, or one workspace package.
+struct Unit {
+    package: Option<workspace::Package>,
+    documents: Vec<Document>,
+    old_version: Version,
+    new_version: Version,
+    tag: String,
+    last_tag: Option<String>,
+    context: hooks::Context,
+    changed_files: Vec<String>,
 }
-impl Version {
-    fn from_str(version: &str) -> Result<Self, String> {
-        let v = VERSION_REGEX
-            .captures(version)
-            .ok_or_else(|| "Invalid version format".to_string())?;
+impl Unit {
+    /// Read `targets` and work out the new version and tag for `arg` (a level or version),
+    /// asking for it interactively when `None`.
+    fn plan(
+        settings: &Settings,
+        package: Option<workspace::Package>,
+        targets: &[config::Target],
+        arg: Option<&str>,
+        from_tag: bool,
+    ) -> Result<Self, String> {
+        let mut documents = targets
+            .iter()
+            .map(Document::read)
+            .collect::<Result<Vec<_>, _>>()?;
+        let name = package.as_ref().map(|p| p.name.as_str());
+        let dir = package
+            .as_ref()
+            .map(|p| p.dir.to_string_lossy().into_owned());
+        let file_path = documents[0].target.path.clone();
+        let old_version = documents[0].parsed_json.get_version().clone();
+        // Recorded now so that a failed run still reports the files it was bumping.
+        report::record(|report| {
+            report
+                .files
+                .extend(documents.iter().map(|document| report::File {
+                    path: document.target.path.clone(),
+                    key: document.target.key.clone(),
+                    package: name.map(String::from),
+                    old_version: document.parsed_json.get_version().to_string(),
+                    new_version: None,
+                }))
+        });
+        check_variants(settings, &documents)?;
+
+        // stdin is read as a pure filter, without looking at the repository around it.
+        let stdin = file_path == "-";
+        let last_tag = git::git_last_tag(&settings.tag_glob(name)).filter(|_| !stdin);
+        let tag_version = highest_tag_version(settings, name).filter(|_| !stdin);
+        if let Some(tag_version) = &tag_version {
+            if *tag_version != old_version {
+                report::warning(format!(
+                    "{} is at {} but the highest tag reachable from HEAD is {}",
+                    file_path,
+                    old_version,
+                    settings.tag_for(name, &tag_version.to_string())
+                ));
+            }
+        }
+        let new_version = if from_tag {
+            tag_version.ok_or_else(|| "No version tag reachable from HEAD".to_string())?
+        } else {
+            old_version.clone()
+        };
+
+        let arg = match arg {
+            Some(arg) => arg.to_string(),
+            None => interactive::choose_version(
+                name,
+                &new_version,
+                &settings.preid,
+                scheme::resolve(settings.calver.as_deref())?.as_ref(),
+                &mut std::io::stdin().lock(),
+            )?,
+        };
+        let query = match arg.as_str() {
+            "auto" => auto_query(last_tag.as_deref(), dir.as_deref())?,
+            x => Query::parse(x, &settings.preid)?,
+        };
+        if let Query::Version(version) = &query {
+            if *version < new_version && !settings.allow_downgrade {
+                return Err(format!(
+                    "{} would go down from {} to {} (pass --allow-downgrade to do it anyway)",
+                    file_path, new_version, version
+                ));
+            }
+            if *version == new_version && !settings.allow_same {
+                return Err(format!(
+                    "{} is already at {} (pass --allow-same to re-release it)",
+                    file_path, version
+                ));
+            }
+        }
+        let level = query.name();
+        let new_version = scheme::resolve(settings.calver.as_deref())?.bump(&new_version, query)?;
+        report::record(|report| {
+            for file in report.files.iter_mut().filter(|f| f.new_version.is_none()) {
+                file.new_version = Some(new_version.to_string());
+            }
+        });
+        if settings.chrome {
+            new_version.check_max(webext::VERSION_PART_MAX)?;
+        }
+        for document in &mut documents {
+            document.plan_builds(&new_version, settings)?;
+            document.validate(&new_version, settings.validate)?;
+        }
+        let tag = settings.tag_for(name, &new_version.to_string());
+        if !stdin && git::git_tag_exists(&tag) {
+            if settings.git {
+                return Err(format!("tag {} already exists", tag));
+            }
+            report::warning(format!("tag {} already exists", tag));
+        }
+
+        let context = hooks::Context {
+            old_version: old_version.to_string(),
+            new_version: new_version.to_string(),
+            file: file_path,
+            level: level.to_string(),
+            tag: tag.clone(),
+            package: name.unwrap_or_default().to_string(),
+        };
         Ok(Self {
-            major: v.get(1).unwrap().as_str().parse().unwrap(),
-            minor: v.get(2).unwrap().as_str().parse().unwrap(),
-            patch: v.get(3).unwrap().as_str().parse().unwrap(),
+            package,
+            documents,
+            old_version,
+            new_version,
+            tag,
+            last_tag,
+            context,
+            changed_files: Vec::new(),
+        })
+    }
+
+    /// Where this unit's changelog goes: inside the package directory for workspace packages.
+    fn changelog_path(&self, changelog: &str) -> PathBuf {
+        changelog_path(self.package.as_ref(), changelog)
+    }
+}
+
+/// Where the changelog is: a workspace package keeps its own, named like `changelog`, in
+/// its directory.
+fn changelog_path(package: Option<&workspace::Package>, changelog: &str) -> PathBuf {
+    match package {
+        Some(package) => package
+            .dir
+            .join(Path::new(changelog).file_name().unwrap_or_default()),
+        None => PathBuf::from(changelog),
+    }
+}
+
+/// The package named by `--package` and the files to read, for the subcommands that look at
+/// a single manifest. A workspace needs `--package` to tell which.
+fn subcommand_targets(
+    matches: &ArgMatches,
+    settings: &Settings,
+) -> Result<(Option<workspace::Package>, Vec<config::Target>), String> {
+    let workspace = match &settings.workspace {
+        Some(workspace) => workspace,
+        None => return Ok((None, settings.files.clone())),
+    };
+    let name = matches
+        .value_of("package")
+        .ok_or_else(|| "--package is required in a workspace".to_string())?;
+    let package = workspace::select(workspace::discover(workspace)?, &[name])?.remove(0);
+    let targets = package.targets(&workspace.files);
+    Ok((Some(package), targets))
+}
+
+/// Whether `package` has commits touching its directory since its last tag (or has never
+/// been tagged).
+fn has_changes(settings: &Settings, package: &workspace::Package) -> Result<bool, String> {
+    let last_tag = match git::git_last_tag(&settings.tag_glob(Some(&package.name))) {
+        Some(tag) => tag,
+        None => return Ok(true),
+    };
+    let dir = package.dir.to_string_lossy();
+    Ok(!git::git_log(Some(&last_tag), "HEAD", Some(&dir))?.is_empty())
+}
+
+/// Print the bumped and skipped packages of a `--changed` run as a table.
+fn print_changed(units: &[Unit], skipped: Vec<(String, Version)>) {
+    let mut rows: Vec<[String; 3]> = units
+        .iter()
+        .filter_map(|unit| {
+            Some([
+                unit.package.as_ref()?.name.clone(),
+                "bumped".to_string(),
+                format!("v{} -> v{}", unit.old_version, unit.new_version),
+            ])
         })
+        .chain(
+            skipped
+                .into_iter()
+                .map(|(name, version)| [name, "skipped".to_string(), format!("v{}", version)]),
+        )
+        .collect();
+    rows.sort();
+    print_table(&rows);
+}
+
+/// Print `rows` as left-aligned columns with a header.
+fn print_table(rows: &[[String; 3]]) {
+    let header = ["package", "status", "version"].map(String::from);
+    let mut widths = [0; 3];
+    for row in std::iter::once(&header).chain(rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in std::iter::once(&header).chain(rows) {
+        println!(
+            "{:w0$}  {:w1$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            w0 = widths[0],
+            w1 = widths[1]
+        );
     }
+}
 
-    fn bump(&mut self, query: Query) {
-        match query {
-            Query::Major => {
-                self.major += 1;
-                self.minor = 0;
-                self.patch = 0;
+/// The units committed together: all of them, or one per commit with `commit-per-package`.
+fn commit_groups<'u>(settings: &Settings, units: &'u [Unit]) -> Vec<&'u [Unit]> {
+    let commit_per_package = settings
+        .workspace
+        .as_ref()
+        .is_some_and(|w| w.commit_per_package);
+    if commit_per_package {
+        units.chunks(1).collect()
+    } else {
+        vec![units]
+    }
+}
+
+fn commit_message(settings: &Settings, group: &[Unit]) -> String {
+    let tags: Vec<&str> = group.iter().map(|unit| unit.tag.as_str()).collect();
+    group[0]
+        .context
+        .expand(&settings.message.replace("{tags}", &tags.join(", ")))
+}
+
+/// Print what the bump is about to do: the changed lines of each file and the git commands.
+fn print_plan(settings: &Settings, units: &[Unit]) {
+    for unit in units {
+        for document in &unit.documents {
+            eprintln!("{}", document.target.path);
+            let old = document.parsed_json.emb_string();
+            let new = document.render(&unit.new_version);
+            for (old_line, new_line) in old.lines().zip(new.lines()) {
+                if old_line != new_line {
+                    eprintln!("-{}\n+{}", old_line, new_line);
+                }
             }
-            Query::Minor => {
-                self.minor += 1;
-                self.patch = 0;
+        }
+        if let Some(changelog) = &settings.changelog {
+            eprintln!(
+                "{}: add release notes for {}",
+                unit.changelog_path(changelog).display(),
+                unit.new_version
+            );
+        }
+        if let Some(updates) = &settings.updates {
+            for path in updates.firefox.iter().chain(&updates.chrome) {
+                eprintln!("{}: add {}", unit.context.expand(path), unit.new_version);
             }
-            Query::Patch => self.patch += 1,
-            Query::Version(v) => {
-                *self = v;
+        }
+    }
+    if settings.git {
+        for group in commit_groups(settings, units) {
+            eprintln!("git commit -m {:?}", commit_message(settings, group));
+            for unit in group {
+                eprintln!("git tag {}", unit.tag);
             }
         }
+        if let Some(remote) = &settings.push {
+            eprintln!("git push --atomic {} HEAD and the new tags", remote);
+        }
     }
 }
-impl ToString for Version {
-    fn to_string(&self) -> String {
-        format!("{}.{}.{}", self.major, self.minor, self.patch)
+
+/// The update manifests of `unit` with the new version added, as `(path, contents)`. `zip`
+/// is the packed extension, if it was packed.
+fn update_manifests(
+    settings: &Settings,
+    updates: &config::Updates,
+    unit: &Unit,
+    zip: Option<&Path>,
+) -> Result<Vec<(String, String)>, String> {
+    let manifest = Path::new(&unit.documents[0].target.path);
+    let version = unit.new_version.to_string();
+    let zip_path = match zip {
+        Some(zip) => zip.to_path_buf(),
+        None => pack::zip_path(manifest, &version, &settings.pack)?,
+    };
+    let zip_name = zip_path.file_name().unwrap_or_default().to_string_lossy();
+    let url = updates
+        .url
+        .as_deref()
+        .map(|url| unit.context.expand(url).replace("{zip}", &zip_name))
+        .unwrap_or_default();
+    let hash = match (updates.hash, zip) {
+        (true, Some(zip)) => Some(updates::sha256(zip)?),
+        _ => None,
+    };
+
+    let mut files = Vec::new();
+    if let Some(path) = &updates.firefox {
+        let path = unit.context.expand(path);
+        let id = match &updates.firefox_id {
+            Some(id) => id.clone(),
+            None => unit
+                .documents
+                .iter()
+                .find_map(|document| webext::gecko_id(&document.parsed_json.emb_string()))
+                .ok_or_else(|| {
+                    "no add-on ID: set `updates.firefox-id` or browser_specific_settings.gecko.id"
+                        .to_string()
+                })?,
+        };
+        let existing = std::fs::read_to_string(&path).ok();
+        let json = updates::firefox(existing.as_deref(), &id, &version, &url, hash.as_deref())
+            .map_err(|e| format!("{}: {}", path, e))?;
+        files.push((path, json));
     }
+    if let (Some(path), Some(id)) = (&updates.chrome, &updates.chrome_id) {
+        let xml = updates::chrome(id, &version, &url, hash.as_deref());
+        files.push((unit.context.expand(path), xml));
+    }
+    Ok(files)
 }
 
-static VERSION_REGEX: Lazy<regex::Regex> =
-    Lazy::new(|| regex::Regex::new(r"^(\d+)\.(\d+)\.(\d+)$").unwrap());
+/// The results of the run for CI, prefixed with the package name in a workspace.
+fn outputs(units: &[Unit]) -> output::Outputs {
+    let mut outputs = Vec::new();
+    for unit in units {
+        let prefix = match &unit.package {
+            Some(package) => format!("{}_", package.name),
+            None => String::new(),
+        };
+        for (name, value) in [
+            ("old_version", unit.old_version.to_string()),
+            ("new_version", unit.new_version.to_string()),
+            ("tag", unit.tag.clone()),
+            ("changed_files", unit.changed_files.join(" ")),
+        ] {
+            outputs.push((format!("{}{}", prefix, name), value));
+        }
+    }
+    outputs
+}
+
+fn run_hooks(settings: &Settings, stage: hooks::Stage, units: &[Unit]) -> Result<(), String> {
+    units
+        .iter()
+        .try_for_each(|unit| hooks::run_hook(stage, settings.hooks.get(stage), &unit.context))
+}
+
+/// Files that appear in `git status` now but did not in `before`.
+fn hook_changes(before: &[String]) -> Result<Vec<String>, String> {
+    Ok(git::git_status()?
+        .into_iter()
+        .filter(|entry| !before.contains(entry))
+        .map(|entry| entry[3..].to_string())
+        .collect())
+}
+
+/// Print the completion script for `shell`, offering the levels of the configured scheme and
+/// the packages of the configured workspace.
+fn print_completions(matches: &ArgMatches, settings: &Settings) -> Result<(), String> {
+    let shell: Shell = matches.value_of("shell").unwrap().parse()?;
+    let levels = scheme::resolve(settings.calver.as_deref())?.accepted_levels();
+    let packages = match &settings.workspace {
+        Some(workspace) => workspace::discover(workspace)?,
+        None => Vec::new(),
+    };
+    let packages: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+    let completions = Completions {
+        levels: &levels,
+        packages: &packages,
+    };
+    let script = completions::script(create_app(&completions), crate_name!(), shell, &levels)?;
+    print!("{}", script);
+    Ok(())
+}
+
+/// `expect` for the `String` errors used throughout: the message is printed as `error:` on
+/// stderr and in the `--json` report alike, and the run exits with status 1.
+trait OrFail<T> {
+    fn or_fail(self, context: &str) -> T;
+}
+impl<T, E: std::fmt::Display> OrFail<T> for Result<T, E> {
+    fn or_fail(self, context: &str) -> T {
+        self.unwrap_or_else(|e| report::fail(1, format!("{}: {}", context, e)))
+    }
+}
+
+/// Parse the command line. With `--json` anywhere in it, usage errors are reported as JSON too.
+fn get_matches<'a>() -> ArgMatches<'a> {
+    let json = std::env::args().any(|arg| arg == "--json");
+    let mut app = create_app(&Completions::default());
+    if json {
+        app = app.setting(AppSettings::ColorNever);
+    }
+    match app.get_matches_safe() {
+        Ok(matches) => matches,
+        Err(e) if json && e.use_stderr() => {
+            eprintln!("{}", e.message);
+            // The first paragraph has the error; usage and hints follow.
+            let message = e.message.split("\n\n").next().unwrap_or_default();
+            let message: Vec<&str> = message.lines().map(str::trim).collect();
+            report::start();
+            report::finish(Some(
+                message.join(" ").trim_start_matches("error: ").to_string(),
+            ));
+            std::process::exit(1);
+        }
+        Err(e) => e.exit(),
+    }
+}
 
 fn main() {
-    let app = create_app();
-    let matches = app.get_matches();
-
-    let arg = matches.value_of("version").unwrap_or("patch");
-    let query = match arg {
-        "major" => Query::Major,
-        "minor" => Query::Minor,
-        "patch" => Query::Patch,
-        x => Query::Version(Version::from_str(x).unwrap()),
+    let matches = get_matches();
+    if matches.is_present("json") {
+        report::start();
+    }
+
+    if let Some(command) = ["man", "completions"]
+        .iter()
+        .find(|command| matches.subcommand_matches(command).is_some())
+    {
+        if report::is_json() {
+            report::fail(1, format!("--json cannot be used with {}", command));
+        }
+    }
+
+    if matches.subcommand_matches("man").is_some() {
+        let page = man::render(create_app(&Completions::default()), subcommands())
+            .or_fail("Failed to render the man page");
+        print!("{}", page);
+        return;
+    }
+
+    let (config, config_source) = Config::discover().or_fail("Failed to load config");
+
+    if let Some(matches) = matches.subcommand_matches("completions") {
+        let settings = Settings::resolve(config, matches).or_fail("Invalid config");
+        print_completions(matches, &settings).or_fail("Failed to print completions");
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("config") {
+        let settings = Settings::resolve(config, matches).or_fail("Invalid config");
+        if report::is_json() {
+            let settings = serde_json::to_value(&settings).or_fail("Failed to print config");
+            report::output(serde_json::json!({
+                "source": config_source.map(|source| source.display().to_string()),
+                "settings": settings,
+            }));
+        } else {
+            if let Some(source) = config_source {
+                println!("# {}", source.display());
+            }
+            print!("{}", settings.to_toml().or_fail("Failed to print config"));
+        }
+        report::finish(None);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("notes") {
+        let settings = Settings::resolve(config, matches).or_fail("Invalid config");
+        print_notes(matches, &settings).or_fail("Failed to print release notes");
+        report::finish(None);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("check") {
+        // Exit 1 means the range is not satisfied, so errors get 2.
+        let satisfied = Settings::resolve(config, matches)
+            .map_err(|e| format!("Invalid config: {}", e))
+            .and_then(|settings| {
+                check(matches, &settings).map_err(|e| format!("Failed to check version: {}", e))
+            })
+            .unwrap_or_else(|e| report::fail(2, e));
+        report::finish(None);
+        std::process::exit(if satisfied { 0 } else { 1 });
+    }
+
+    if let Some(matches) = matches.subcommand_matches("snapshot") {
+        let settings = Settings::resolve(config, matches).or_fail("Invalid config");
+        let version = snapshot(matches, &settings).or_fail("Failed to make a snapshot");
+        if report::is_json() {
+            report::output(serde_json::json!({ "version": version.to_string() }));
+        } else {
+            println!("{}", version);
+        }
+        report::finish(None);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("pack") {
+        let settings = Settings::resolve(config, matches).or_fail("Invalid config");
+        let (_, targets) = subcommand_targets(matches, &settings).or_fail("Failed to pack");
+        let version = Document::read(&targets[0])
+            .or_fail("Failed to read manifest")
+            .parsed_json
+            .get_version()
+            .to_string();
+        let zip = pack::pack(Path::new(&targets[0].path), &version, &settings.pack)
+            .or_fail("Failed to pack");
+        if report::is_json() {
+            report::output(serde_json::json!({ "zip": zip.display().to_string() }));
+        } else {
+            println!("{}", zip.display());
+        }
+        report::finish(None);
+        return;
+    }
+
+    let settings = Settings::resolve(config, &matches).or_fail("Invalid config");
+    if settings.files[0].path == "-" {
+        report::take_stdout();
+    }
+    if !is_version_query(&settings.level) {
+        report::fail(1, format!("Invalid level in config: {}", settings.level));
+    }
+
+    if settings.git {
+        git::git_current_branch()
+            .and_then(|branch| settings.check_branch(&branch))
+            .or_fail("Refusing to bump");
+    }
+
+    let interactive = matches.is_present("interactive");
+    let arg = if interactive {
+        None
+    } else {
+        Some(matches.value_of("version").unwrap_or(&settings.level))
     };
+    let from_tag = matches.is_present("from-tag");
+    let mut skipped: Vec<(String, Version)> = Vec::new();
+    let mut units = match &settings.workspace {
+        Some(workspace) => {
+            let names: Vec<&str> = matches
+                .values_of("package")
+                .map(|values| values.collect())
+                .unwrap_or_default();
+            let mut packages = workspace::discover(workspace)
+                .and_then(|packages| workspace::select(packages, &names))
+                .or_fail("Failed to find workspace packages");
+            if packages.is_empty() {
+                report::fail(1, "No packages found in the workspace".to_string());
+            }
+            if matches.is_present("changed") {
+                let mut changed = Vec::new();
+                for package in packages {
+                    if has_changes(&settings, &package).or_fail("Failed to read git history") {
+                        changed.push(package);
+                    } else {
+                        let document = Document::read(&package.targets(&workspace.files)[0])
+                            .or_fail("Failed to read package");
+                        skipped.push((package.name, document.parsed_json.get_version().clone()));
+                    }
+                }
+                packages = changed;
+            }
+            packages
+                .into_iter()
+                .map(|package| {
+                    let targets = package.targets(&workspace.files);
+                    Unit::plan(&settings, Some(package), &targets, arg, from_tag)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        }
+        None => Unit::plan(&settings, None, &settings.files, arg, from_tag).map(|unit| vec![unit]),
+    }
+    .or_fail("Failed to bump");
+    if units.is_empty() {
+        if report::is_json() {
+            report::finish(None);
+        } else {
+            print_changed(&units, skipped);
+        }
+        return;
+    }
+    let documents: usize = units.iter().map(|unit| unit.documents.len()).sum();
+    if settings.output.is_some() && documents != 1 {
+        report::fail(
+            1,
+            format!(
+                "--output needs a single file to bump, but {} would be bumped",
+                documents
+            ),
+        );
+    }
+    if interactive {
+        print_plan(&settings, &units);
+        let apply = interactive::confirm("Apply?", &mut std::io::stdin().lock())
+            .or_fail("Failed to read answer");
+        if !apply {
+            eprintln!("Aborted, nothing was changed.");
+            std::process::exit(1);
+        }
+    }
+
+    let mut rollback = Rollback::default();
 
-    let file_path = matches.value_of("file-path").unwrap();
+    run_hooks(&settings, hooks::Stage::PreBump, &units).unwrap_or_else(|e| rollback.abort(e));
 
-    let manifest_path = Path::new(file_path);
-    let manifest_str = std::fs::read_to_string(manifest_path).unwrap();
-    let mut parsed_json = parse_json(manifest_str).expect("Failed to Parse Json");
+    for unit in &mut units {
+        for document in &unit.documents {
+            let contents = document.render(&unit.new_version);
+            if document.target.path == "-" {
+                print!("{}", contents);
+                if !contents.ends_with('\n') {
+                    println!();
+                }
+                continue;
+            }
+            let path = settings.output.as_ref().unwrap_or(&document.target.path);
+            rollback
+                .write(Path::new(path), &contents)
+                .unwrap_or_else(|e| rollback.abort(e));
+            if !unit.changed_files.contains(path) {
+                unit.changed_files.push(path.clone());
+            }
+        }
 
-    if !parsed_json.has_version() {
-        panic!("No version found in manifest.json");
+        if let Some(changelog) = &settings.changelog {
+            let changelog_path = unit.changelog_path(changelog);
+            let dir = unit
+                .package
+                .as_ref()
+                .map(|p| p.dir.to_string_lossy().into_owned());
+            update_changelog(
+                &changelog_path,
+                &unit.new_version,
+                settings.changelog_pattern.as_deref(),
+                unit.last_tag.as_deref(),
+                dir.as_deref(),
+            )
+            .and_then(|text| rollback.write(&changelog_path, &text))
+            .unwrap_or_else(|e| rollback.abort(format!("Failed to update changelog: {}", e)));
+            unit.changed_files
+                .push(changelog_path.to_string_lossy().into_owned());
+        }
     }
 
-    let before_version = parsed_json.get_version().to_string();
-    parsed_json.get_version_mut().bump(query);
-    std::fs::write(manifest_path, parsed_json.emb_string()).unwrap();
+    let status_before = if settings.stage_hook_changes {
+        git::git_status().unwrap_or_else(|e| rollback.abort(e))
+    } else {
+        Vec::new()
+    };
+
+    run_hooks(&settings, hooks::Stage::PostBump, &units).unwrap_or_else(|e| rollback.abort(e));
 
-    if matches.is_present("after-run") {
-        let after_run = matches.value_of("after-run").unwrap();
-        if cfg!(target_os = "windows") {
-            std::process::Command::new("cmd")
-                .args(&["/C", after_run])
-                .status()
+    let mut packed: Vec<PathBuf> = Vec::new();
+    for unit in &mut units {
+        let zip = if settings.pack.on_bump {
+            let manifest = Path::new(&unit.documents[0].target.path);
+            let version = unit.new_version.to_string();
+            let zip = pack::zip_path(manifest, &version, &settings.pack)
+                .map(|zip| rollback.track(&zip))
+                .and_then(|_| pack::pack(manifest, &version, &settings.pack))
+                .unwrap_or_else(|e| rollback.abort(format!("Failed to pack: {}", e)));
+            eprintln!("packed {}", zip.display());
+            packed.extend(zip.canonicalize());
+            Some(zip)
         } else {
-            std::process::Command::new("sh")
-                .arg("-c")
-                .arg(after_run)
-                .status()
+            None
+        };
+        if let Some(updates) = &settings.updates {
+            let files = update_manifests(&settings, updates, unit, zip.as_deref())
+                .unwrap_or_else(|e| rollback.abort(format!("Failed to update manifests: {}", e)));
+            for (path, contents) in files {
+                rollback
+                    .write(Path::new(&path), &contents)
+                    .unwrap_or_else(|e| rollback.abort(e));
+                unit.changed_files.push(path);
+            }
         }
-        .expect("Failed to run after-run");
     }
 
-    if matches.is_present("git") {
-        let is_signature = matches.is_present("signature");
-        let message = matches.value_of("message").map(|x| x.to_string());
-        git::git_commit_and_tag(
-            parsed_json.get_version(),
-            is_signature,
-            message,
-            manifest_path.to_str().unwrap(),
-        )
-        .expect("Failed to commit and tag");
+    if settings.git {
+        let mut staged: Vec<String> = Vec::new();
+        for group in commit_groups(&settings, &units) {
+            run_hooks(&settings, hooks::Stage::PreCommit, group)
+                .unwrap_or_else(|e| rollback.abort(e));
+
+            let mut paths: Vec<String> = Vec::new();
+            for path in group.iter().flat_map(|unit| unit.changed_files.iter()) {
+                if !paths.contains(path) {
+                    paths.push(path.clone());
+                }
+            }
+            if settings.stage_hook_changes {
+                for path in hook_changes(&status_before).unwrap_or_else(|e| rollback.abort(e)) {
+                    let is_zip = Path::new(&path)
+                        .canonicalize()
+                        .is_ok_and(|path| packed.contains(&path));
+                    if !is_zip && !paths.contains(&path) && !staged.contains(&path) {
+                        paths.push(path);
+                    }
+                }
+            }
+            staged.extend(paths.iter().cloned());
+
+            let tags: Vec<String> = group.iter().map(|unit| unit.tag.clone()).collect();
+            let message = commit_message(&settings, group);
+            let parent = git::git_head().ok();
+            git::git_commit(&message, settings.sign, &paths)
+                .unwrap_or_else(|e| rollback.abort(format!("Failed to commit: {}", e)));
+            // Only a commit that is really there may be rolled back, or the reset would take
+            // the user's own commit with it.
+            let head = git::git_head()
+                .ok()
+                .filter(|head| Some(head) != parent.as_ref());
+            let head = head.unwrap_or_else(|| {
+                rollback.abort("Failed to commit: HEAD did not move".to_string())
+            });
+            rollback.committed(parent.as_deref());
+            for tag in &tags {
+                git::git_tag(tag)
+                    .unwrap_or_else(|e| rollback.abort(format!("Failed to tag: {}", e)));
+                rollback.tagged(tag);
+            }
+            report::record(|report| {
+                report.commits.push(head);
+                report.tags.extend(tags.iter().cloned());
+            });
+
+            run_hooks(&settings, hooks::Stage::PostTag, group)
+                .unwrap_or_else(|e| rollback.abort(e));
+        }
+
+        if let Some(remote) = &settings.push {
+            let result = git::git_push(remote, &rollback.tags);
+            if let Err(e) = result {
+                if settings.rollback_on_failure {
+                    rollback.abort(format!("Failed to push: {}", e));
+                }
+                report::fail(1, format!("Failed to push: {}", e));
+            }
+        }
     }
 
-    println!(
-        "v{} -> v{}",
-        before_version,
-        parsed_json.get_version().to_string()
-    );
+    let outputs = outputs(&units);
+    if let Some(path) = std::env::var_os("GITHUB_OUTPUT").filter(|path| !path.is_empty()) {
+        output::append(Path::new(&path), &output::github(&outputs))
+            .or_fail("Failed to write GITHUB_OUTPUT");
+    }
+    if let Some(path) = matches.value_of("output-env") {
+        std::fs::write(path, output::dotenv(&outputs)).or_fail("Failed to write --output-env");
+    }
+
+    if report::is_json() {
+        report::finish(None);
+        return;
+    }
+    if matches.is_present("changed") {
+        print_changed(&units, skipped);
+        return;
+    }
+    for unit in &units {
+        let line = match &unit.package {
+            Some(package) => format!(
+                "{}: v{} -> v{}",
+                package.name, unit.old_version, unit.new_version
+            ),
+            None => format!("v{} -> v{}", unit.old_version, unit.new_version),
+        };
+        if report::stdout_reserved() {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
 }
@@ -0,0 +1,66 @@
+use clap::{crate_description, crate_name, crate_version, App};
+
+/// Help is wrapped at a fixed width so the page does not depend on the terminal it was made in.
+const WIDTH: usize = 80;
+
+/// `text` as roff: trailing spaces dropped, backslashes escaped and lines that would read as
+/// requests guarded.
+fn escape(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.trim_end().replace('\\', "\\e");
+            if line.starts_with('.') || line.starts_with('\'') {
+                format!("\\&{}\n", line)
+            } else {
+                format!("{}\n", line)
+            }
+        })
+        .collect()
+}
+
+fn long_help(app: &mut App) -> Result<String, String> {
+    let mut help = Vec::new();
+    app.write_long_help(&mut help).map_err(|e| e.to_string())?;
+    String::from_utf8(help).map_err(|e| e.to_string())
+}
+
+/// A man page for `app` with a section for each of `subcommands`. The help clap prints is
+/// kept as it is, in no-fill mode, so the page always matches `--help`.
+pub fn render(app: App, subcommands: Vec<App>) -> Result<String, String> {
+    let mut page = format!(
+        ".TH {} 1 \"\" \"{} {}\" \"User Commands\"\n\
+         .SH NAME\n\
+         {} \\- {}\n\
+         .SH DESCRIPTION\n\
+         .nf\n",
+        crate_name!().to_uppercase(),
+        crate_name!(),
+        crate_version!(),
+        crate_name!().replace('-', "\\-"),
+        escape(crate_description!()).trim_end()
+    );
+    page += &escape(&long_help(&mut app.set_term_width(WIDTH))?);
+    page += ".fi\n.SH SUBCOMMANDS\n";
+    for subcommand in subcommands {
+        let name = format!("{} {}", crate_name!(), subcommand.get_name());
+        page += &format!(".SS {}\n.nf\n", name.replace('-', "\\-"));
+        page += &escape(&long_help(
+            &mut subcommand.bin_name(name).set_term_width(WIDTH),
+        )?);
+        page += ".fi\n";
+    }
+    Ok(page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_roff() {
+        assert_eq!(
+            escape("a\\b\n.not a request\n'nor this\n  .fine"),
+            "a\\eb\n\\&.not a request\n\\&'nor this\n  .fine\n"
+        );
+    }
+}
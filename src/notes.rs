@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use serde_json::json;
+
+use crate::changelog::{self, Grouping, Release};
+use crate::config::Settings;
+use crate::git;
+use crate::workspace::Package;
+
+/// Release notes for `version`, taken from its changelog section when there is one and
+/// otherwise from the commits between the previous tag and the tag of `version` (or HEAD
+/// when that tag does not exist yet). In a workspace the tags and commits are those of
+/// `package`. Also returns which of the two was used.
+pub fn release_notes(
+    version: &str,
+    changelog_path: &Path,
+    settings: &Settings,
+    package: Option<&Package>,
+) -> Result<(Release, &'static str), String> {
+    if let Ok(text) = std::fs::read_to_string(changelog_path) {
+        if let Some(release) = changelog::find_release(&text, version) {
+            return Ok((release, "changelog"));
+        }
+    }
+
+    let name = package.map(|package| package.name.as_str());
+    let tag = settings.tag_for(name, version);
+    let (until, since, date) = if git::git_tag_exists(&tag) {
+        let since = git::git_last_tag_before(&format!("{}^", tag), &settings.tag_glob(name));
+        let date = git::git_date(&tag)?;
+        (tag.as_str(), since, Some(date))
+    } else {
+        ("HEAD", git::git_last_tag(&settings.tag_glob(name)), None)
+    };
+    let dir = package.map(|package| package.dir.to_string_lossy());
+    let commits = git::git_log(since.as_deref(), until, dir.as_deref())?;
+    let release =
+        Release::from_commits(version.to_string(), date, &commits, &Grouping::Conventional);
+    Ok((release, "commits"))
+}
+
+pub fn to_json(release: &Release, source: &str) -> serde_json::Value {
+    json!({
+        "version": release.version,
+        "date": release.date,
+        "source": source,
+        "sections": release.sections.iter().map(|section| json!({
+            "title": section.title,
+            "entries": section.entries,
+        })).collect::<Vec<_>>(),
+        "body": release.body(),
+    })
+}
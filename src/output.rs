@@ -0,0 +1,75 @@
+use std::io::Write;
+use std::path::Path;
+
+/// The results of a run handed to CI, as `(name, value)` pairs such as
+/// `("new_version", "1.2.4")`.
+pub type Outputs = Vec<(String, String)>;
+
+/// `outputs` in the `name=value` lines GitHub Actions reads from `$GITHUB_OUTPUT`.
+pub fn github(outputs: &Outputs) -> String {
+    outputs
+        .iter()
+        .map(|(name, value)| format!("{}={}\n", name, value))
+        .collect()
+}
+
+/// `outputs` as a dotenv file: upper-case names, double-quoted values.
+pub fn dotenv(outputs: &Outputs) -> String {
+    outputs
+        .iter()
+        .map(|(name, value)| {
+            let name: String = name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_uppercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("{}=\"{}\"\n", name, value)
+        })
+        .collect()
+}
+
+/// Append `text` to the file at `path`, creating it if needed; GitHub Actions shares one
+/// `$GITHUB_OUTPUT` file between the commands of a step.
+pub fn append(path: &Path, text: &str) -> Result<(), String> {
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outputs() -> Outputs {
+        vec![
+            ("new_version".to_string(), "1.2.4".to_string()),
+            ("popup-tag".to_string(), "popup@1.2.4".to_string()),
+            ("changed_files".to_string(), "a \"b\".json".to_string()),
+        ]
+    }
+
+    #[test]
+    fn github_format() {
+        assert_eq!(
+            github(&outputs()),
+            "new_version=1.2.4\npopup-tag=popup@1.2.4\nchanged_files=a \"b\".json\n"
+        );
+    }
+
+    #[test]
+    fn dotenv_format() {
+        assert_eq!(
+            dotenv(&outputs()),
+            "NEW_VERSION=\"1.2.4\"\nPOPUP_TAG=\"popup@1.2.4\"\nCHANGED_FILES=\"a \\\"b\\\".json\"\n"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+
+use crate::config::Pack;
+
+/// The files to pack from `dir`, relative to it and sorted: everything not ignored by
+/// `.gitignore` (or hidden), narrowed by the include and exclude globs, minus `out_dir`.
+pub fn files(dir: &Path, pack: &Pack) -> Result<Vec<PathBuf>, String> {
+    let mut overrides = OverrideBuilder::new(dir);
+    for glob in &pack.include {
+        overrides.add(glob).map_err(|e| e.to_string())?;
+    }
+    for glob in &pack.exclude {
+        overrides
+            .add(&format!("!{}", glob))
+            .map_err(|e| e.to_string())?;
+    }
+    let overrides = overrides.build().map_err(|e| e.to_string())?;
+    let out_dir = dir.join(&pack.out_dir);
+
+    let mut files = Vec::new();
+    for entry in WalkBuilder::new(dir)
+        .overrides(overrides)
+        .require_git(false)
+        .build()
+    {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        if !entry.file_type().is_some_and(|t| t.is_file()) || path.starts_with(&out_dir) {
+            continue;
+        }
+        let relative = path.strip_prefix(dir).map_err(|e| e.to_string())?;
+        files.push(relative.to_path_buf());
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Zip `files` (relative to `dir`) into `out`. Entries are written in order with a fixed
+/// timestamp and permissions, so the same files always give the same bytes.
+pub fn write_zip(dir: &Path, files: &[PathBuf], out: &Path) -> Result<(), String> {
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    let mut zip = ZipWriter::new(&mut buffer);
+    let options = FileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644);
+    for file in files {
+        let name = file
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let contents = std::fs::read(dir.join(file)).map_err(|e| format!("{}: {}", name, e))?;
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(&contents).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    drop(zip);
+
+    if let Some(parent) = out.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(out, buffer.into_inner()).map_err(|e| format!("{}: {}", out.display(), e))
+}
+
+/// The zip's base name: the manifest's `name` made file-safe, or the directory name when the
+/// name is missing or localized (`__MSG_appName__`).
+fn archive_name(manifest: &serde_json::Value, dir: &Path) -> String {
+    let name = match manifest.get("name").and_then(|name| name.as_str()) {
+        Some(name) if !name.is_empty() && !name.starts_with("__MSG_") => name.to_string(),
+        _ => std::fs::canonicalize(dir)
+            .ok()
+            .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "extension".to_string()),
+    };
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// The directory of `manifest`, `.` for a bare file name.
+fn manifest_dir(manifest: &Path) -> &Path {
+    match manifest.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Where `pack` puts the zip of `manifest` at `version`: `{out-dir}/{name}-{version}.zip`.
+pub fn zip_path(manifest: &Path, version: &str, pack: &Pack) -> Result<PathBuf, String> {
+    let json =
+        std::fs::read_to_string(manifest).map_err(|e| format!("{}: {}", manifest.display(), e))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&json).map_err(|e| format!("{}: {}", manifest.display(), e))?;
+    let name = archive_name(&value, manifest_dir(manifest));
+    Ok(manifest
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(&pack.out_dir)
+        .join(format!("{}-{}.zip", name, version)))
+}
+
+/// Pack the directory of `manifest` into its `zip_path`, returning that path.
+pub fn pack(manifest: &Path, version: &str, pack: &Pack) -> Result<PathBuf, String> {
+    let dir = manifest_dir(manifest);
+    let out = zip_path(manifest, version, pack)?;
+    let files = files(dir, pack)?;
+    if files.is_empty() {
+        return Err(format!("nothing to pack in {}", dir.display()));
+    }
+    write_zip(dir, &files, &out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_names() {
+        let dir = Path::new("ext");
+        let manifest = serde_json::json!({ "name": "My Extension" });
+        assert_eq!(archive_name(&manifest, dir), "my-extension");
+        let manifest = serde_json::json!({ "name": "__MSG_appName__" });
+        assert_eq!(archive_name(&manifest, dir), "extension");
+    }
+}
@@ -1,13 +1,35 @@
 use std::{iter::Peekable, str::Chars};
 use uuid::Uuid;
 
-use crate::Version;
+use crate::version::Version;
 
+#[derive(Clone)]
 pub struct ParsedJson {
     template: String,
     key: String,
     pub version: Option<Version>,
+    /// Build numbers, in the order of the build keys passed to the parser.
+    pub builds: Vec<BuildNumber>,
+    /// The array or object found at the container key path, if one was asked for.
+    pub container: Option<Container>,
 }
+
+/// An integer field rewritten alongside the version, written back as a number or a string
+/// like it was found.
+#[derive(Clone)]
+pub struct BuildNumber {
+    key: String,
+    quoted: bool,
+    pub value: Option<u64>,
+}
+
+/// An array or object kept out of the template so that elements can be added to it.
+#[derive(Clone)]
+pub struct Container {
+    key: String,
+    text: String,
+}
+
 impl ParsedJson {
     pub fn has_version(&self) -> bool {
         self.version.is_some()
@@ -22,27 +44,208 @@ impl ParsedJson {
     }
 
     pub fn emb_string(&self) -> String {
-        self.template
-            .replace(&self.key, &self.get_version().to_string())
+        let mut json = match &self.version {
+            Some(version) => self.template.replace(&self.key, &version.to_string()),
+            None => self.template.clone(),
+        };
+        if let Some(container) = &self.container {
+            json = json.replace(&container.key, &container.text);
+        }
+        for build in &self.builds {
+            if let Some(value) = build.value {
+                let value = if build.quoted {
+                    format!("\"{}\"", value)
+                } else {
+                    value.to_string()
+                };
+                json = json.replace(&build.key, &value);
+            }
+        }
+        json
+    }
+
+    /// Append `entry` to the array, laid out like the elements already in it: on one line
+    /// for a one-line array, otherwise on its own line with the same indentation. Returns
+    /// `false` if no array was found.
+    pub fn push_to_array(&mut self, entry: &serde_json::Value) -> bool {
+        self.add_to_container('[', None, entry)
+    }
+
+    /// Add the member `name` to the object, laid out like `push_to_array` does. Returns
+    /// `false` if no object was found.
+    pub fn insert_member(&mut self, name: &str, value: &serde_json::Value) -> bool {
+        self.add_to_container('{', Some(name), value)
+    }
+
+    fn add_to_container(
+        &mut self,
+        open: char,
+        name: Option<&str>,
+        value: &serde_json::Value,
+    ) -> bool {
+        let container = match &mut self.container {
+            Some(container) if container.text.starts_with(open) => container,
+            _ => return false,
+        };
+        let start = self.template.find(&container.key).unwrap();
+        let line = self.template[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_indent: String = self.template[line..]
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+
+        let text = &container.text;
+        let close = &text[text.len() - 1..];
+        let inner = &text[1..text.len() - 1];
+        let body = inner.trim_end();
+        let closing = &inner[body.len()..];
+        let name = |colon: &str| {
+            name.map(|name| format!("{}{}", serde_json::Value::from(name), colon))
+                .unwrap_or_default()
+        };
+        container.text = if body.trim_start().is_empty() {
+            let indent = format!("{}  ", line_indent);
+            format!(
+                "{}\n{}{}{}\n{}{}",
+                open,
+                indent,
+                name(": "),
+                pretty(value, "  ", &indent),
+                line_indent,
+                close
+            )
+        } else if !inner.contains('\n') {
+            let (separator, colon) = if inner.contains(", ") {
+                (", ", ": ")
+            } else {
+                (",", ":")
+            };
+            format!(
+                "{}{}{}{}{}{}{}",
+                open,
+                body,
+                separator,
+                name(colon),
+                value,
+                closing,
+                close
+            )
+        } else {
+            let leading = &inner[..inner.len() - inner.trim_start().len()];
+            let indent = leading.rsplit('\n').next().unwrap_or_default();
+            let unit = match indent.strip_prefix(line_indent.as_str()) {
+                Some(unit) if !unit.is_empty() => unit,
+                _ => "  ",
+            };
+            format!(
+                "{}{},\n{}{}{}{}{}",
+                open,
+                body,
+                indent,
+                name(": "),
+                pretty(value, unit, indent),
+                closing,
+                close
+            )
+        };
+        true
     }
 }
 
+/// `value` pretty-printed with `unit` per level, its lines after the first indented by `indent`.
+fn pretty(value: &serde_json::Value, unit: &str, indent: &str) -> String {
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(unit.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    serde::Serialize::serialize(value, &mut serializer).unwrap();
+    String::from_utf8(out)
+        .unwrap()
+        .replace('\n', &format!("\n{}", indent))
+}
+
+#[cfg(test)]
 pub fn parse_json(json: impl Into<String>) -> Result<ParsedJson, String> {
-    Ok(Parser::parse(json))
+    parse_json_at(json, "version")
+}
+
+/// Parse `json`, taking the version from the dotted key path `key` (e.g. `expo.version`).
+#[cfg(test)]
+pub fn parse_json_at(json: impl Into<String>, key: &str) -> Result<ParsedJson, String> {
+    parse_json_with_builds(json, key, &[])
+}
+
+/// Parse `json`, taking the version from the dotted key path `key` and integer build
+/// numbers from the dotted key paths in `build_keys`.
+pub fn parse_json_with_builds(
+    json: impl Into<String>,
+    key: &str,
+    build_keys: &[&str],
+) -> Result<ParsedJson, String> {
+    let split = |key: &str| key.split('.').map(String::from).collect();
+    Parser::parse(
+        json,
+        split(key),
+        build_keys.iter().map(|key| split(key)).collect(),
+    )
 }
 
+/// Parse `json`, keeping the array or object at the key path `path` (the whole document
+/// when empty) aside for `push_to_array` and `insert_member`. The path is given as segments
+/// since keys such as add-on IDs may contain dots.
+pub fn parse_json_container(json: impl Into<String>, path: &[&str]) -> Result<ParsedJson, String> {
+    let json: String = json.into();
+    let mut parser = Parser::new(json.chars());
+    parser.target = Vec::new();
+    parser.container_target = Some(path.iter().map(|s| s.to_string()).collect());
+    let template = parser.content()?;
+    parser.parsed_json.template = template;
+    if path.is_empty() {
+        let text = json.trim();
+        let key = Uuid::new_v4().to_string();
+        let start = json.find(text).unwrap_or(0);
+        parser.parsed_json.template =
+            format!("{}{}{}", &json[..start], key, &json[start + text.len()..]);
+        parser.parsed_json.container = Some(Container {
+            key,
+            text: text.to_string(),
+        });
+    }
+    Ok(parser.parsed_json)
+}
+
+/// Stands in for an array in `Parser::path`, so keys inside arrays never match the target.
+const ARRAY_MARKER: &str = "\u{0}[]";
+
 pub struct Parser<'a> {
     json: Peekable<Chars<'a>>,
     parsed_json: ParsedJson,
-    nested: usize,
+    target: Vec<String>,
+    build_targets: Vec<Vec<String>>,
+    /// Key path of the array or object to keep aside.
+    container_target: Option<Vec<String>>,
+    path: Vec<String>,
 }
 impl<'a> Parser<'a> {
-    pub fn parse(json: impl Into<String>) -> ParsedJson {
+    pub fn parse(
+        json: impl Into<String>,
+        target: Vec<String>,
+        build_targets: Vec<Vec<String>>,
+    ) -> Result<ParsedJson, String> {
         let json: String = json.into();
         let mut parser = Parser::new(json.chars());
-        let template = parser.content();
+        parser.target = target;
+        parser.parsed_json.builds = build_targets
+            .iter()
+            .map(|_| BuildNumber {
+                key: Uuid::new_v4().to_string(),
+                quoted: false,
+                value: None,
+            })
+            .collect();
+        parser.build_targets = build_targets;
+        let template = parser.content()?;
         parser.parsed_json.template = template;
-        parser.parsed_json
+        Ok(parser.parsed_json)
     }
 
     pub fn new(json: Chars<'a>) -> Self {
@@ -52,41 +255,44 @@ impl<'a> Parser<'a> {
                 template: String::new(),
                 key: Uuid::new_v4().to_string(),
                 version: None,
+                builds: Vec::new(),
+                container: None,
             },
-            nested: 0,
+            target: vec!["version".to_string()],
+            build_targets: Vec::new(),
+            container_target: None,
+            path: Vec::new(),
         }
     }
 
-    fn content(&mut self) -> String {
+    fn content(&mut self) -> Result<String, String> {
         let mut content = String::new();
         while let Some(c) = self.json.next() {
             content.push(c);
             content += match c {
-                '{' => self.object(),
-                '[' => self.array(),
+                '{' => self.object()?,
+                '[' => self.array()?,
                 '"' => self.string(),
-                '0'..='9' | 'a'..='z' | 'E' | '.' | '-' | '+' => self.num_like(),
+                '0'..='9' | 'a'..='z' | 'E' | '.' | '-' | '+' => self.num_like()?,
                 ' ' => continue,
-                _ => panic!("unexpected char: {}", c),
+                _ => return Err(format!("unexpected char: {}", c)),
             }
             .as_str();
             break;
         }
-        content
+        Ok(content)
     }
 
-    fn object(&mut self) -> String {
+    fn object(&mut self) -> Result<String, String> {
         let mut object = String::new();
-        self.nested += 1;
         if let Some(&c) = self.json.peek() {
             if c == '}' {
                 self.json.next();
                 object.push(c);
-                self.nested -= 1;
-                return object;
+                return Ok(object);
             }
         } else {
-            panic!("unexpected end of json");
+            return Err("unexpected end of json".to_string());
         }
         while let Some(&c) = self.json.peek() {
             if is_whitespace(c) {
@@ -96,7 +302,7 @@ impl<'a> Parser<'a> {
             }
             break;
         }
-        object += self.object_body().as_str();
+        object += self.object_body()?.as_str();
         loop {
             if let Some(&c) = self.json.peek() {
                 match c {
@@ -108,28 +314,27 @@ impl<'a> Parser<'a> {
                     ',' => {
                         self.json.next();
                         object.push(c);
-                        object += self.object_body().as_str();
+                        object += self.object_body()?.as_str();
                     }
                     x if is_whitespace(x) => {
                         self.json.next();
                         object.push(c);
                         continue;
                     }
-                    _ => panic!("unexpected char: {}", c),
+                    _ => return Err(format!("unexpected char: {}", c)),
                 }
             } else {
-                panic!("unexpected end of json");
+                return Err("unexpected end of json".to_string());
             }
         }
-        self.nested -= 1;
-        object
+        Ok(object)
     }
 
-    fn object_body(&mut self) -> String {
+    fn object_body(&mut self) -> Result<String, String> {
         let mut object_body = String::new();
         let mut key = String::new();
 
-        while let Some(c) = self.json.next() {
+        for c in self.json.by_ref() {
             object_body.push(c);
             if is_whitespace(c) {
                 continue;
@@ -137,12 +342,12 @@ impl<'a> Parser<'a> {
             if c == '"' {
                 key = self.string();
                 object_body += key.as_str();
-                key = key.trim_end_matches("\"").to_string();
+                key = key.trim_end_matches('"').to_string();
                 break;
             }
-            panic!("unexpected char: {}", c);
+            return Err(format!("unexpected char: {}", c));
         }
-        while let Some(c) = self.json.next() {
+        for c in self.json.by_ref() {
             object_body.push(c);
             if is_whitespace(c) {
                 continue;
@@ -150,7 +355,7 @@ impl<'a> Parser<'a> {
             if c == ':' {
                 break;
             }
-            panic!("unexpected char: {}", c);
+            return Err(format!("unexpected char: {}", c));
         }
         while let Some(&c) = self.json.peek() {
             if is_whitespace(c) {
@@ -161,27 +366,46 @@ impl<'a> Parser<'a> {
                 break;
             }
         }
-        let value = self.content();
-        if self.nested == 1 && key == "version" {
-            if let Ok(version) = Version::from_str(value.trim_matches('\"')) {
-                if self.parsed_json.version.is_some() {
-                    panic!("duplicate version");
-                }
-                self.parsed_json.version = Some(version);
-                let key_with_quote = format!("\"{}\"", self.parsed_json.key);
-                object_body.push_str(&key_with_quote);
-            } else {
-                panic!("invalid version: {}", value);
+        self.path.push(key);
+        let value = self.content()?;
+        let is_target = self.path == self.target;
+        let build_target = self.build_targets.iter().position(|t| *t == self.path);
+        let is_container = self.container_target.as_ref() == Some(&self.path)
+            && (value.starts_with('[') || value.starts_with('{'));
+        self.path.pop();
+        if is_target {
+            let version = Version::from_str(value.trim_matches('\"'))
+                .map_err(|e| format!("invalid version: {} ({})", value, e))?;
+            if self.parsed_json.version.is_some() {
+                return Err("duplicate version".to_string());
             }
+            self.parsed_json.version = Some(version);
+            let key_with_quote = format!("\"{}\"", self.parsed_json.key);
+            object_body.push_str(&key_with_quote);
+        } else if let Some(i) = build_target {
+            let build = &mut self.parsed_json.builds[i];
+            if build.value.is_some() {
+                return Err("duplicate build number".to_string());
+            }
+            build.quoted = value.starts_with('"');
+            build.value = match value.trim_matches('"').parse() {
+                Ok(n) => Some(n),
+                Err(_) => return Err(format!("invalid build number: {}", value)),
+            };
+            object_body.push_str(&build.key);
+        } else if is_container {
+            let key = Uuid::new_v4().to_string();
+            object_body.push_str(&key);
+            self.parsed_json.container = Some(Container { key, text: value });
         } else {
             object_body += value.as_str();
         }
-        object_body
+        Ok(object_body)
     }
 
-    fn array(&mut self) -> String {
+    fn array(&mut self) -> Result<String, String> {
         let mut array = String::new();
-        self.nested += 1;
+        self.path.push(ARRAY_MARKER.to_string());
         loop {
             if let Some(&c) = self.json.peek() {
                 match c {
@@ -202,18 +426,18 @@ impl<'a> Parser<'a> {
                     _ => (),
                 }
             } else {
-                panic!("unexpected end of json");
+                return Err("unexpected end of json".to_string());
             }
-            array += self.content().as_str();
+            array += self.content()?.as_str();
         }
-        self.nested -= 1;
-        array
+        self.path.pop();
+        Ok(array)
     }
 
     fn string(&mut self) -> String {
         let mut string = String::new();
         let mut is_escaped = false;
-        while let Some(c) = self.json.next() {
+        for c in self.json.by_ref() {
             string.push(c);
             match c {
                 '"' if !is_escaped => break,
@@ -225,7 +449,7 @@ impl<'a> Parser<'a> {
         string
     }
 
-    fn num_like(&mut self) -> String {
+    fn num_like(&mut self) -> Result<String, String> {
         let mut num_like = String::new();
         while let Some(&c) = self.json.peek() {
             match c {
@@ -236,10 +460,10 @@ impl<'a> Parser<'a> {
                 }
                 ']' | ',' | '}' => break,
                 x if is_whitespace(x) => break,
-                _ => panic!("unexpected char: {}", c),
+                _ => return Err(format!("unexpected char: {}", c)),
             }
         }
-        num_like
+        Ok(num_like)
     }
 }
 
@@ -254,117 +478,115 @@ mod tests {
     #[test]
     fn string_simple() {
         let mut parser = Parser::new(r#""hello""#.chars());
-        assert_eq!(r#""hello""#.to_string(), parser.content());
+        assert_eq!(r#""hello""#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn string_include_double_quote() {
         let mut parser = Parser::new(r#""hello\"""#.chars());
-        assert_eq!(r#""hello\"""#.to_string(), parser.content());
+        assert_eq!(r#""hello\"""#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn string_include_backslash() {
         let mut parser = Parser::new(r#""hello\\""#.chars());
-        assert_eq!(r#""hello\\""#.to_string(), parser.content());
+        assert_eq!(r#""hello\\""#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn string_include_backslash_and_double_quote() {
         let mut parser = Parser::new(r#""hello\\\"""#.chars());
-        assert_eq!(r#""hello\\\"""#.to_string(), parser.content());
+        assert_eq!(r#""hello\\\"""#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn num_like_simple() {
         let mut parser = Parser::new(r#"123"#.chars());
-        assert_eq!(r#"123"#.to_string(), parser.content());
+        assert_eq!(r#"123"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn num_like_simple_with_dot() {
         let mut parser = Parser::new(r#"123.456"#.chars());
-        assert_eq!(r#"123.456"#.to_string(), parser.content());
+        assert_eq!(r#"123.456"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn num_like_simple_with_dot_and_e() {
         let mut parser = Parser::new(r#"123.456e7"#.chars());
-        assert_eq!(r#"123.456e7"#.to_string(), parser.content());
+        assert_eq!(r#"123.456e7"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn num_like_simple_with_dot_and_e_and_minus() {
         let mut parser = Parser::new(r#"123.456e-7"#.chars());
-        assert_eq!(r#"123.456e-7"#.to_string(), parser.content());
+        assert_eq!(r#"123.456e-7"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn num_like_simple_with_dot_and_e_and_minus_and_plus() {
         let mut parser = Parser::new(r#"123.456e-7+"#.chars());
-        assert_eq!(r#"123.456e-7+"#.to_string(), parser.content());
+        assert_eq!(r#"123.456e-7+"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn num_like_true() {
         let mut parser = Parser::new(r#"true"#.chars());
-        assert_eq!(r#"true"#.to_string(), parser.content());
+        assert_eq!(r#"true"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn num_like_false() {
         let mut parser = Parser::new(r#"false"#.chars());
-        assert_eq!(r#"false"#.to_string(), parser.content());
+        assert_eq!(r#"false"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn num_like_null() {
         let mut parser = Parser::new(r#"null"#.chars());
-        assert_eq!(r#"null"#.to_string(), parser.content());
+        assert_eq!(r#"null"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
-    #[should_panic(expected = "unexpected char: \"")]
     fn num_like_fail_with_double_quote() {
         let mut parser = Parser::new(r#"tr"ue"#.chars());
-        parser.content();
+        assert_eq!(parser.content(), Err("unexpected char: \"".to_string()));
     }
 
     #[test]
-    #[should_panic(expected = "unexpected char: [")]
     fn num_like_fail_with_left_bracket() {
         let mut parser = Parser::new(r#"tr[ue"#.chars());
-        parser.content();
+        assert_eq!(parser.content(), Err("unexpected char: [".to_string()));
     }
 
     #[test]
     fn array_simple() {
         let mut parser = Parser::new(r#"[1,"x",null]"#.chars());
-        assert_eq!(r#"[1,"x",null]"#.to_string(), parser.content());
+        assert_eq!(r#"[1,"x",null]"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn array_with_space() {
         let mut parser = Parser::new(r#"[1, "x", null]"#.chars());
-        assert_eq!(r#"[1, "x", null]"#.to_string(), parser.content());
+        assert_eq!(r#"[1, "x", null]"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn array_nested() {
         let mut parser = Parser::new(r#"[1,[2,3],null]"#.chars());
-        assert_eq!(r#"[1,[2,3],null]"#.to_string(), parser.content());
+        assert_eq!(r#"[1,[2,3],null]"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn object_simple() {
         let mut parser = Parser::new(r#"{"a":1}"#.chars());
-        assert_eq!(r#"{"a":1}"#.to_string(), parser.content());
+        assert_eq!(r#"{"a":1}"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
     fn object_simple_with_space() {
         let mut parser = Parser::new(r#"{"a": 1}"#.chars());
-        assert_eq!(r#"{"a": 1}"#.to_string(), parser.content());
+        assert_eq!(r#"{"a": 1}"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
@@ -378,14 +600,14 @@ mod tests {
             r#"{
     "a" : 1 }"#
                 .to_string(),
-            parser.content()
+            parser.content().unwrap()
         );
     }
 
     #[test]
     fn object_nested() {
         let mut parser = Parser::new(r#"{"a":{"b":1}}"#.chars());
-        assert_eq!(r#"{"a":{"b":1}}"#.to_string(), parser.content());
+        assert_eq!(r#"{"a":{"b":1}}"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
@@ -405,14 +627,14 @@ mod tests {
     }
 }"#
             .to_string(),
-            parser.content()
+            parser.content().unwrap()
         );
     }
 
     #[test]
     fn object_with_comma() {
         let mut parser = Parser::new(r#"{"a":1, "b":2}"#.chars());
-        assert_eq!(r#"{"a":1, "b":2}"#.to_string(), parser.content());
+        assert_eq!(r#"{"a":1, "b":2}"#.to_string(), parser.content().unwrap());
     }
 
     #[test]
@@ -429,10 +651,23 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "invalid version: \"1.0\"")]
-    #[allow(unused_must_use)]
     fn object_include_invalid_version() {
-        parse_json(r#"{"a":1,"version":"1.0"}"#);
+        let error = parse_json(r#"{"a":1,"version":"1.0"}"#).err().unwrap();
+        assert!(error.starts_with("invalid version: \"1.0\""));
+    }
+
+    #[test]
+    fn object_include_too_large_version() {
+        let error = parse_json(r#"{"version":"99999999999999999999.0.0"}"#)
+            .err()
+            .unwrap();
+        assert!(error.starts_with("invalid version: \"99999999999999999999.0.0\""));
+    }
+
+    #[test]
+    fn unexpected_end_is_an_error() {
+        let error = parse_json(r#"{"a":[1,"#).err().unwrap();
+        assert_eq!(error, "unexpected end of json");
     }
 
     #[test]
@@ -493,4 +728,58 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn object_include_build_numbers() {
+        let mut parsed_json = parse_json_with_builds(
+            "{\"expo\": {\"version\": \"1.2.3\",\n  \"android\": {\"versionCode\": 12 },\n  \"ios\": {\"buildNumber\": \"7\"}}}",
+            "expo.version",
+            &["expo.android.versionCode", "expo.ios.buildNumber"],
+        )
+        .unwrap();
+        assert_eq!(parsed_json.builds[0].value, Some(12));
+        assert_eq!(parsed_json.builds[1].value, Some(7));
+        parsed_json.builds[0].value = Some(13);
+        parsed_json.builds[1].value = Some(8);
+        assert_eq!(
+            parsed_json.emb_string(),
+            "{\"expo\": {\"version\": \"1.2.3\",\n  \"android\": {\"versionCode\": 13 },\n  \"ios\": {\"buildNumber\": \"8\"}}}"
+        );
+    }
+
+    #[test]
+    fn object_include_invalid_build_number() {
+        let error =
+            parse_json_with_builds(r#"{"version":"1.0.0","code":"x"}"#, "version", &["code"])
+                .err()
+                .unwrap();
+        assert_eq!(error, "invalid build number: \"x\"");
+    }
+
+    #[test]
+    fn object_include_version_at_key_path() {
+        let parsed_json = parse_json_at(
+            r#"{"version":"9.9.9","expo":{"name":"x","version":"0.1.0"}}"#,
+            "expo.version",
+        )
+        .unwrap();
+        assert_eq!(
+            parsed_json.version.unwrap().to_string(),
+            "0.1.0".to_string()
+        );
+        assert_eq!(
+            parsed_json.template,
+            format!(
+                "{{\"version\":\"9.9.9\",\"expo\":{{\"name\":\"x\",\"version\":\"{}\"}}}}",
+                parsed_json.key
+            )
+        );
+    }
+
+    #[test]
+    fn object_include_version_in_array() {
+        let parsed_json =
+            parse_json_at(r#"{"expo":[{"version":"0.1.0"}]}"#, "expo.version").unwrap();
+        assert!(parsed_json.version.is_none());
+    }
 }
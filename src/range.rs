@@ -0,0 +1,303 @@
+use once_cell::sync::Lazy;
+
+use crate::version::Version;
+
+/// `1`, `1.2`, `1.2.3`, `1.x`, `1.2.*` or `1.2.3-rc.1`, with an optional leading `v`.
+static PARTIAL_REGEX: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(
+        r"^v?(\d+|[xX*])(?:\.(\d+|[xX*]))?(?:\.(\d+|[xX*]))?(-[0-9A-Za-z-]+(?:\.[0-9A-Za-z-]+)*)?$",
+    )
+    .unwrap()
+});
+
+/// Whitespace between an operator and its version.
+static OPERATOR_SPACE_REGEX: Lazy<regex::Regex> =
+    Lazy::new(|| regex::Regex::new(r"([<>=^~]+)\s+").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparator {
+    op: Op,
+    version: Version,
+}
+impl Comparator {
+    fn new(op: Op, version: Version) -> Self {
+        Self { op, version }
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        match self.op {
+            Op::Gt => version > &self.version,
+            Op::Ge => version >= &self.version,
+            Op::Lt => version < &self.version,
+            Op::Le => version <= &self.version,
+            Op::Eq => version == &self.version,
+        }
+    }
+}
+
+/// A version written with some parts left out or wildcarded.
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Option<String>,
+}
+impl Partial {
+    fn parse(s: &str) -> Result<Self, String> {
+        let caps = PARTIAL_REGEX
+            .captures(s)
+            .ok_or_else(|| format!("invalid version in range: `{}`", s))?;
+        let part = |i: usize| -> Result<Option<u64>, String> {
+            match caps.get(i).map(|m| m.as_str()) {
+                None | Some("x") | Some("X") | Some("*") => Ok(None),
+                Some(n) => n.parse().map(Some).map_err(|e| format!("`{}`: {}", s, e)),
+            }
+        };
+        let (major, minor, patch) = (part(1)?, part(2)?, part(3)?);
+        // Anything after a wildcard is a wildcard too: `1.x.3` means `1.x`.
+        let minor = major.and(minor);
+        let patch = minor.and(patch);
+        let pre = caps.get(4).filter(|_| patch.is_some());
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            pre: pre.map(|m| m.as_str().to_string()),
+        })
+    }
+
+    /// The lowest version matching this partial.
+    fn floor(&self) -> Version {
+        let version = Version::new(
+            self.major.unwrap_or(0),
+            self.minor.unwrap_or(0),
+            self.patch.unwrap_or(0),
+        );
+        match &self.pre {
+            Some(pre) => Version::from_str(&format!("{}{}", version, pre)).unwrap(),
+            None => version,
+        }
+    }
+
+    /// The first version past this partial (`1.2` -> `1.3.0`), if any part was left out.
+    fn ceiling(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (Some(major), None, _) => Some(Version::new(major.saturating_add(1), 0, 0)),
+            (Some(major), Some(minor), None) => {
+                Some(Version::new(major, minor.saturating_add(1), 0))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_any(&self) -> bool {
+        self.major.is_none()
+    }
+}
+
+/// A version range in npm or Cargo syntax: comparators (`>=1.2.0`, `<2`, `=1.4.2`),
+/// caret (`^1.4`) and tilde (`~1.4.2`) ranges, wildcards (`1.x`, `1.2.*`, `*`) and hyphen
+/// ranges (`1.2 - 1.4`). Comparators separated by spaces or commas must all match; `||`
+/// separates alternatives. A bare version is exact, as in npm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    alternatives: Vec<Vec<Comparator>>,
+}
+impl Range {
+    pub fn parse(range: &str) -> Result<Self, String> {
+        let alternatives = range
+            .split("||")
+            .map(parse_set)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { alternatives })
+    }
+
+    /// Whether `version` is in the range. A prerelease only matches when a comparator of the
+    /// same alternative names a prerelease of the same `major.minor.patch`, so `^1.2` does not
+    /// pick up `1.3.0-rc.0`.
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|set| {
+            set.iter().all(|c| c.matches(version))
+                && (!version.is_prerelease()
+                    || set.iter().any(|c| {
+                        c.version.is_prerelease() && c.version.release() == version.release()
+                    }))
+        })
+    }
+}
+
+/// Parse the comparators of one `||` alternative.
+fn parse_set(set: &str) -> Result<Vec<Comparator>, String> {
+    let tokens = tokenize(set);
+    if let [from, dash, to] = tokens.as_slice() {
+        if dash == "-" {
+            let mut comparators = lower_bound(Op::Ge, &Partial::parse(from)?);
+            comparators.extend(upper_bound(Op::Le, &Partial::parse(to)?));
+            return Ok(comparators);
+        }
+    }
+    let mut comparators = Vec::new();
+    for token in &tokens {
+        comparators.extend(parse_comparator(token)?);
+    }
+    Ok(comparators)
+}
+
+/// Split on whitespace and commas, keeping operators attached to their version
+/// (`>= 1.2` is read as `>=1.2`).
+fn tokenize(set: &str) -> Vec<String> {
+    OPERATOR_SPACE_REGEX
+        .replace_all(set, "$1")
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn parse_comparator(token: &str) -> Result<Vec<Comparator>, String> {
+    let split = token
+        .find(|c: char| !matches!(c, '<' | '>' | '=' | '^' | '~'))
+        .ok_or_else(|| format!("missing version in range: `{}`", token))?;
+    let (op, rest) = token.split_at(split);
+    let partial = Partial::parse(rest)?;
+    Ok(match op {
+        "" | "=" => {
+            let mut comparators = lower_bound(Op::Ge, &partial);
+            match partial.ceiling() {
+                Some(_) => comparators.extend(upper_bound(Op::Le, &partial)),
+                None if partial.is_any() => {}
+                None => comparators = vec![Comparator::new(Op::Eq, partial.floor())],
+            }
+            comparators
+        }
+        ">" | ">=" => lower_bound(if op == ">" { Op::Gt } else { Op::Ge }, &partial),
+        "<" | "<=" => upper_bound(if op == "<" { Op::Lt } else { Op::Le }, &partial),
+        "^" => {
+            let mut comparators = lower_bound(Op::Ge, &partial);
+            let ceiling = match (partial.major, partial.minor, partial.patch) {
+                (None, _, _) => None,
+                (Some(0), Some(0), Some(patch)) => {
+                    Some(Version::new(0, 0, patch.saturating_add(1)))
+                }
+                (Some(0), Some(minor), _) => Some(Version::new(0, minor.saturating_add(1), 0)),
+                (Some(major), _, _) => Some(Version::new(major.saturating_add(1), 0, 0)),
+            };
+            comparators.extend(ceiling.map(|v| Comparator::new(Op::Lt, v)));
+            comparators
+        }
+        "~" | "~>" => {
+            let mut comparators = lower_bound(Op::Ge, &partial);
+            let ceiling = match (partial.major, partial.minor) {
+                (None, _) => None,
+                (Some(major), None) => Some(Version::new(major.saturating_add(1), 0, 0)),
+                (Some(major), Some(minor)) => Some(Version::new(major, minor.saturating_add(1), 0)),
+            };
+            comparators.extend(ceiling.map(|v| Comparator::new(Op::Lt, v)));
+            comparators
+        }
+        _ => return Err(format!("unknown operator `{}` in range", op)),
+    })
+}
+
+/// `>partial` / `>=partial`: `>1.2` means past every `1.2.x`.
+fn lower_bound(op: Op, partial: &Partial) -> Vec<Comparator> {
+    if partial.is_any() {
+        return match op {
+            // Nothing is greater than every version.
+            Op::Gt => vec![Comparator::new(Op::Lt, Version::new(0, 0, 0))],
+            _ => Vec::new(),
+        };
+    }
+    match (op, partial.ceiling()) {
+        (Op::Gt, Some(ceiling)) => vec![Comparator::new(Op::Ge, ceiling)],
+        _ => vec![Comparator::new(op, partial.floor())],
+    }
+}
+
+/// `<partial` / `<=partial`: `<=1.2` includes every `1.2.x`.
+fn upper_bound(op: Op, partial: &Partial) -> Vec<Comparator> {
+    if partial.is_any() {
+        return match op {
+            Op::Lt => vec![Comparator::new(Op::Lt, Version::new(0, 0, 0))],
+            _ => Vec::new(),
+        };
+    }
+    match (op, partial.ceiling()) {
+        (Op::Le, Some(ceiling)) => vec![Comparator::new(Op::Lt, ceiling)],
+        _ => vec![Comparator::new(op, partial.floor())],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn satisfies(version: &str, range: &str) -> bool {
+        Range::parse(range)
+            .unwrap()
+            .matches(&Version::from_str(version).unwrap())
+    }
+
+    #[test]
+    fn comparators() {
+        assert!(satisfies("2.1.0", ">=2.1.0"));
+        assert!(!satisfies("2.0.9", ">=2.1.0"));
+        assert!(satisfies("1.9.0", ">=1.2, <2"));
+        assert!(satisfies("1.9.0", ">= 1.2 < 2"));
+        assert!(!satisfies("2.0.0", ">=1.2 <2"));
+        assert!(satisfies("1.3.0", ">1.2"));
+        assert!(!satisfies("1.2.9", ">1.2"));
+        assert!(satisfies("1.2.9", "<=1.2"));
+        assert!(satisfies("1.4.2", "=1.4.2"));
+        assert!(!satisfies("1.4.3", "1.4.2"));
+    }
+
+    #[test]
+    fn caret_and_tilde() {
+        assert!(satisfies("1.9.9", "^1.4"));
+        assert!(!satisfies("1.3.9", "^1.4"));
+        assert!(!satisfies("2.0.0", "^1.4"));
+        assert!(satisfies("0.2.5", "^0.2.3"));
+        assert!(!satisfies("0.3.0", "^0.2.3"));
+        assert!(!satisfies("0.0.4", "^0.0.3"));
+        assert!(satisfies("1.4.9", "~1.4.2"));
+        assert!(!satisfies("1.5.0", "~1.4.2"));
+        assert!(satisfies("1.9.0", "~1"));
+    }
+
+    #[test]
+    fn wildcards_hyphens_and_alternatives() {
+        assert!(satisfies("1.2.7", "1.2.x"));
+        assert!(satisfies("1.9.0", "1.*"));
+        assert!(!satisfies("2.0.0", "1"));
+        assert!(satisfies("5.0.0", "*"));
+        assert!(satisfies("1.4.0", "1.2 - 1.4"));
+        assert!(!satisfies("1.5.0", "1.2 - 1.4"));
+        assert!(satisfies("3.1.0", "^1.0 || ^3.0"));
+        assert!(!satisfies("2.1.0", "^1.0 || ^3.0"));
+    }
+
+    #[test]
+    fn prereleases_need_opt_in() {
+        assert!(!satisfies("1.5.0-rc.0", "^1.4"));
+        assert!(satisfies("1.4.0-rc.1", ">=1.4.0-rc.0"));
+        assert!(!satisfies("1.4.1-rc.1", ">=1.4.0-rc.0"));
+        assert!(satisfies("1.4.0", ">=1.4.0-rc.0"));
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        assert!(Range::parse("^").is_err());
+        assert!(Range::parse("1.2.3.4").is_err());
+        assert!(Range::parse("!1.2").is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+/// A version file as read and bumped.
+#[derive(Debug, Serialize)]
+pub struct File {
+    pub path: String,
+    /// Dotted key path of the version in the file.
+    pub key: String,
+    pub package: Option<String>,
+    pub old_version: String,
+    /// `None` when the run failed before the new version was worked out.
+    pub new_version: Option<String>,
+}
+
+/// A hook command that was run.
+#[derive(Debug, Serialize)]
+pub struct Hook {
+    pub stage: &'static str,
+    pub command: String,
+    /// `None` when the command could not be started or was killed by a signal.
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+}
+
+/// What a run did, printed as one JSON document with `--json`.
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub ok: bool,
+    pub files: Vec<File>,
+    pub hooks: Vec<Hook>,
+    pub commits: Vec<String>,
+    pub tags: Vec<String>,
+    pub warnings: Vec<String>,
+    pub error: Option<String>,
+    /// What a subcommand such as `check` or `snapshot` would otherwise print.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
+}
+
+/// The report of this run, `None` unless `--json` was given.
+static REPORT: Lazy<Mutex<Option<Report>>> = Lazy::new(|| Mutex::new(None));
+
+/// Set when stdout carries the bumped file (`-f -`).
+static STDOUT_TAKEN: AtomicBool = AtomicBool::new(false);
+
+fn with_report<T>(f: impl FnOnce(&mut Option<Report>) -> T) -> T {
+    let mut report = REPORT.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut report)
+}
+
+/// Start collecting the report. An unexpected panic from here on prints it with the panic
+/// message as its `error`, so even those come out in the same schema as successes.
+pub fn start() {
+    with_report(|report| *report = Some(Report::default()));
+    std::panic::set_hook(Box::new(|info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| info.to_string());
+        eprintln!("error: {}", message);
+        finish(Some(message));
+    }));
+}
+
+/// Whether the run is reported as JSON, so stdout must carry nothing else.
+pub fn is_json() -> bool {
+    with_report(|report| report.is_some())
+}
+
+/// Reserve stdout for the bumped file.
+pub fn take_stdout() {
+    STDOUT_TAKEN.store(true, Ordering::Relaxed);
+}
+
+/// Whether stdout is reserved for the JSON report or the bumped file, so that hook, git and
+/// progress output has to go to stderr.
+pub fn stdout_reserved() -> bool {
+    is_json() || STDOUT_TAKEN.load(Ordering::Relaxed)
+}
+
+/// Add to the report, if one is being collected.
+pub fn record(f: impl FnOnce(&mut Report)) {
+    with_report(|report| {
+        if let Some(report) = report {
+            f(report);
+        }
+    });
+}
+
+/// Print `message` as a warning on stderr and add it to the report.
+pub fn warning(message: String) {
+    eprintln!("warning: {}", message);
+    record(|report| report.warnings.push(message));
+}
+
+/// Set the result of a subcommand.
+pub fn output(value: serde_json::Value) {
+    record(|report| report.output = Some(value));
+}
+
+/// Print `message` as the error of the run on stderr (and in the report) and exit with `code`.
+pub fn fail(code: i32, message: String) -> ! {
+    eprintln!("error: {}", message);
+    finish(Some(message));
+    std::process::exit(code);
+}
+
+/// Print the report on stdout, as failed with `error` if given.
+pub fn finish(error: Option<String>) {
+    let report = with_report(|report| report.take());
+    if let Some(mut report) = report {
+        report.ok = error.is_none();
+        report.error = error;
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    }
+}
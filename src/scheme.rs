@@ -0,0 +1,262 @@
+use std::convert::TryInto;
+
+use crate::date::Date;
+use crate::version::{Query, Version, LEVELS};
+
+/// How the next version is computed from the current one.
+pub trait Scheme {
+    /// The levels offered by `--interactive`.
+    fn levels(&self) -> &[&'static str];
+
+    /// The levels offered by shell completions: `levels`, led by `auto` when every level it
+    /// can infer from Conventional Commits is accepted.
+    fn accepted_levels(&self) -> Vec<&'static str> {
+        let levels = self.levels();
+        let auto = ["major", "minor", "patch"]
+            .iter()
+            .all(|level| levels.contains(level));
+        auto.then_some("auto")
+            .into_iter()
+            .chain(levels.iter().copied())
+            .collect()
+    }
+
+    fn bump(&self, current: &Version, query: Query) -> Result<Version, String>;
+}
+
+/// Semantic versioning: `major.minor.patch[-prerelease]`.
+pub struct SemVer;
+impl Scheme for SemVer {
+    fn levels(&self) -> &[&'static str] {
+        &LEVELS
+    }
+
+    fn bump(&self, current: &Version, query: Query) -> Result<Version, String> {
+        let mut next = current.clone();
+        next.bump(query)?;
+        Ok(next)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    /// Full year, `2026`.
+    FullYear,
+    /// Year since 2000, `26`; `0Y` pads it to two digits.
+    ShortYear {
+        padded: bool,
+    },
+    Month {
+        padded: bool,
+    },
+    Day {
+        padded: bool,
+    },
+    /// Counter for releases within the same date.
+    Micro,
+}
+impl Token {
+    fn parse(s: &str) -> Result<Self, String> {
+        Ok(match s {
+            "YYYY" => Token::FullYear,
+            "YY" => Token::ShortYear { padded: false },
+            "0Y" => Token::ShortYear { padded: true },
+            "MM" => Token::Month { padded: false },
+            "0M" => Token::Month { padded: true },
+            "DD" => Token::Day { padded: false },
+            "0D" => Token::Day { padded: true },
+            "MICRO" => Token::Micro,
+            _ => return Err(format!("unknown calver token `{}`", s)),
+        })
+    }
+
+    /// The value of a date token on `date`, `None` for `MICRO`.
+    fn value(&self, date: Date) -> Option<u64> {
+        match self {
+            Token::FullYear => Some(date.year as u64),
+            Token::ShortYear { .. } => Some((date.year - 2000) as u64),
+            Token::Month { .. } => Some(u64::from(date.month)),
+            Token::Day { .. } => Some(u64::from(date.day)),
+            Token::Micro => None,
+        }
+    }
+
+    fn width(&self) -> usize {
+        match self {
+            Token::ShortYear { padded: true }
+            | Token::Month { padded: true }
+            | Token::Day { padded: true } => 2,
+            _ => 0,
+        }
+    }
+}
+
+/// Calendar versioning with a format such as `YYYY.MM.MICRO` or `YY.0M.DD`: the date parts
+/// come from `today`, and `MICRO` counts releases within the same date (from 0).
+pub struct CalVer {
+    tokens: [Token; 3],
+    today: Date,
+}
+impl CalVer {
+    pub fn new(format: &str, today: Date) -> Result<Self, String> {
+        let tokens = format
+            .split('.')
+            .map(Token::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        let tokens: [Token; 3] = tokens
+            .try_into()
+            .map_err(|_| format!("calver format `{}` must have three parts", format))?;
+        if tokens.iter().filter(|t| **t == Token::Micro).count() > 1 {
+            return Err(format!(
+                "calver format `{}` has more than one MICRO",
+                format
+            ));
+        }
+        if today.year < 2000 && tokens.iter().any(|t| matches!(t, Token::ShortYear { .. })) {
+            return Err("short years need a date after 2000".to_string());
+        }
+        Ok(Self { tokens, today })
+    }
+}
+impl Scheme for CalVer {
+    /// There is only one next version, requested as the default `patch` level.
+    fn levels(&self) -> &[&'static str] {
+        &["patch"]
+    }
+
+    fn bump(&self, current: &Version, query: Query) -> Result<Version, String> {
+        match query {
+            Query::Version(version) => return Ok(version),
+            Query::Patch => {}
+            other => {
+                return Err(format!(
+                    "calver versions are bumped with `patch` or an explicit version, not `{}`",
+                    other.name()
+                ))
+            }
+        }
+        let (major, minor, patch) = current.release();
+        let current_parts = [major, minor, patch];
+        let date_parts = self.tokens.map(|token| token.value(self.today));
+        let same_date = date_parts
+            .iter()
+            .zip(current_parts)
+            .all(|(date, current)| date.is_none_or(|date| date == current));
+
+        let mut parts = [0; 3];
+        for (i, part) in parts.iter_mut().enumerate() {
+            *part = match date_parts[i] {
+                Some(value) => value,
+                None if same_date && !current.is_prerelease() => current_parts[i]
+                    .checked_add(1)
+                    .ok_or_else(|| format!("MICRO {} cannot be incremented", current_parts[i]))?,
+                None => 0,
+            };
+        }
+        if same_date && !self.tokens.contains(&Token::Micro) && !current.is_prerelease() {
+            return Err(format!(
+                "{} is already today's version; add MICRO to the calver format to release \
+                 more than once per date",
+                current
+            ));
+        }
+        let next = Version::padded(parts, self.tokens.map(|token| token.width()));
+        if next < *current {
+            return Err(format!(
+                "calver version {} would be lower than the current {}",
+                next, current
+            ));
+        }
+        Ok(next)
+    }
+}
+
+/// The scheme for a run: CalVer when a `calver` format is configured, SemVer otherwise.
+pub fn resolve(calver: Option<&str>) -> Result<Box<dyn Scheme>, String> {
+    Ok(match calver {
+        Some(format) => Box::new(CalVer::new(format, Date::today()?)?),
+        None => Box::new(SemVer),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bumped(format: &str, today: Date, current: &str) -> Result<String, String> {
+        CalVer::new(format, today)?
+            .bump(&Version::from_str(current).unwrap(), Query::Patch)
+            .map(|version| version.to_string())
+    }
+
+    fn date(year: i64, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    #[test]
+    fn new_date_resets_micro() {
+        let today = date(2026, 10, 18);
+        assert_eq!(
+            bumped("YYYY.MM.MICRO", today, "2026.9.4").unwrap(),
+            "2026.10.0"
+        );
+        assert_eq!(bumped("YY.0M.DD", today, "26.09.30").unwrap(), "26.10.18");
+        assert_eq!(
+            bumped("YY.0M.DD", date(2027, 1, 5), "26.10.18").unwrap(),
+            "27.01.5"
+        );
+        assert_eq!(
+            bumped("YY.0M.0D", date(2027, 1, 5), "26.10.18").unwrap(),
+            "27.01.05"
+        );
+    }
+
+    #[test]
+    fn same_date_increments_micro() {
+        let today = date(2026, 10, 18);
+        assert_eq!(
+            bumped("YYYY.MM.MICRO", today, "2026.10.0").unwrap(),
+            "2026.10.1"
+        );
+        assert_eq!(
+            bumped("YYYY.MM.MICRO", today, "2026.10.0-rc.1").unwrap(),
+            "2026.10.0"
+        );
+        assert!(bumped("YY.0M.DD", today, "26.10.18").is_err());
+    }
+
+    #[test]
+    fn only_patch_and_explicit_versions() {
+        let calver = CalVer::new("YYYY.MM.MICRO", date(2026, 10, 18)).unwrap();
+        let current = Version::from_str("2026.10.0").unwrap();
+        for level in ["major", "minor", "premajor", "prerelease"] {
+            let query = Query::parse(level, "rc").unwrap();
+            assert!(calver.bump(&current, query).unwrap_err().contains(level));
+        }
+        let query = Query::parse("2027.1.0", "rc").unwrap();
+        assert_eq!(
+            calver.bump(&current, query).unwrap().to_string(),
+            "2027.1.0"
+        );
+    }
+
+    #[test]
+    fn accepted_levels() {
+        assert_eq!(SemVer.accepted_levels()[..2], ["auto", "patch"]);
+        let calver = CalVer::new("YYYY.MM.MICRO", date(2026, 10, 18)).unwrap();
+        assert_eq!(calver.accepted_levels(), ["patch"]);
+    }
+
+    #[test]
+    fn clock_going_backwards_is_an_error() {
+        assert!(bumped("YYYY.MM.MICRO", date(2026, 10, 18), "2027.1.0").is_err());
+    }
+
+    #[test]
+    fn invalid_formats() {
+        let today = date(2026, 10, 18);
+        assert!(CalVer::new("YYYY.MM", today).is_err());
+        assert!(CalVer::new("YYYY.MM.WW", today).is_err());
+        assert!(CalVer::new("YYYY.MICRO.MICRO", today).is_err());
+    }
+}
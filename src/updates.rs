@@ -0,0 +1,213 @@
+use std::path::Path;
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::parser::parse_json_container;
+
+/// The SHA-256 of the file at `path`, in lowercase hex.
+pub fn sha256(path: &Path) -> Result<String, String> {
+    let contents = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(Sha256::digest(contents)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Firefox's `updates.json` (`existing`, if there is one) with an entry for `version` added
+/// to the updates of add-on `id`, listing the add-on first if needed. The rest of the file is
+/// kept as it was; a version already listed is left alone.
+pub fn firefox(
+    existing: Option<&str>,
+    id: &str,
+    version: &str,
+    link: &str,
+    hash: Option<&str>,
+) -> Result<String, String> {
+    let mut entry = json!({ "version": version, "update_link": link });
+    if let Some(hash) = hash {
+        entry["update_hash"] = json!(format!("sha256:{}", hash));
+    }
+    let existing = match existing {
+        Some(existing) if !existing.trim().is_empty() => existing,
+        _ => {
+            let updates = json!({ "addons": { id: { "updates": [entry] } } });
+            return Ok(serde_json::to_string_pretty(&updates).unwrap() + "\n");
+        }
+    };
+
+    let value: Value =
+        serde_json::from_str(existing).map_err(|e| format!("invalid updates.json: {}", e))?;
+    if !value.is_object() {
+        return Err("updates.json is not an object".to_string());
+    }
+    let parsed = match value.get("addons") {
+        None => {
+            let mut parsed = parse_json_container(existing, &[])?;
+            parsed.insert_member("addons", &json!({ id: { "updates": [entry] } }));
+            parsed
+        }
+        Some(addons) if !addons.is_object() => {
+            return Err("`addons` is not an object".to_string());
+        }
+        Some(addons) => match addons.get(id) {
+            None => {
+                let mut parsed = parse_json_container(existing, &["addons"])?;
+                parsed.insert_member(id, &json!({ "updates": [entry] }));
+                parsed
+            }
+            Some(addon) => match addon.get("updates") {
+                Some(Value::Array(entries)) => {
+                    if entries
+                        .iter()
+                        .any(|e| e.get("version") == Some(&json!(version)))
+                    {
+                        return Ok(existing.to_string());
+                    }
+                    let mut parsed = parse_json_container(existing, &["addons", id, "updates"])?;
+                    parsed.push_to_array(&entry);
+                    parsed
+                }
+                Some(_) => return Err(format!("`addons.{}.updates` is not an array", id)),
+                None => return Err(format!("`addons.{}` has no `updates`", id)),
+            },
+        },
+    };
+    let mut json = parsed.emb_string();
+    if existing.ends_with('\n') && !json.ends_with('\n') {
+        json.push('\n');
+    }
+    Ok(json)
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&apos;")
+        .replace('"', "&quot;")
+}
+
+/// Chrome's `update.xml` pointing extension `app_id` at `version`. Chrome only reads one
+/// `updatecheck` per extension, so the whole file is written anew.
+pub fn chrome(app_id: &str, version: &str, codebase: &str, hash: Option<&str>) -> String {
+    let hash = hash
+        .map(|hash| format!(" hash_sha256='{}'", hash))
+        .unwrap_or_default();
+    format!(
+        "<?xml version='1.0' encoding='UTF-8'?>\n\
+         <gupdate xmlns='http://www.google.com/update2/response' protocol='2.0'>\n  \
+         <app appid='{}'>\n    \
+         <updatecheck codebase='{}' version='{}'{} />\n  \
+         </app>\n\
+         </gupdate>\n",
+        escape_xml(app_id),
+        escape_xml(codebase),
+        escape_xml(version),
+        hash
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPDATES: &str = r#"{
+    "addons": {
+        "test@example.com": {
+            "updates": [
+                { "version": "1.0.0", "update_link": "https://example.com/1.0.0.zip" }
+            ]
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn firefox_appends_keeping_formatting() {
+        let json = firefox(
+            Some(UPDATES),
+            "test@example.com",
+            "1.1.0",
+            "https://example.com/1.1.0.zip",
+            Some("abc"),
+        )
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{
+    "addons": {
+        "test@example.com": {
+            "updates": [
+                { "version": "1.0.0", "update_link": "https://example.com/1.0.0.zip" },
+                {
+                    "update_hash": "sha256:abc",
+                    "update_link": "https://example.com/1.1.0.zip",
+                    "version": "1.1.0"
+                }
+            ]
+        }
+    }
+}
+"#
+        );
+        let again = firefox(Some(&json), "test@example.com", "1.1.0", "x", None).unwrap();
+        assert_eq!(again, json);
+    }
+
+    #[test]
+    fn firefox_creates_missing_entries() {
+        let json = firefox(None, "test@example.com", "1.0.0", "https://x/1.zip", None).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["addons"]["test@example.com"]["updates"][0]["version"],
+            "1.0.0"
+        );
+    }
+
+    #[test]
+    fn firefox_adds_addon_keeping_formatting() {
+        let json = firefox(Some(UPDATES), "other@example.com", "2.0.0", "y", None).unwrap();
+        assert_eq!(
+            json,
+            r#"{
+    "addons": {
+        "test@example.com": {
+            "updates": [
+                { "version": "1.0.0", "update_link": "https://example.com/1.0.0.zip" }
+            ]
+        },
+        "other@example.com": {
+            "updates": [
+                {
+                    "update_link": "y",
+                    "version": "2.0.0"
+                }
+            ]
+        }
+    }
+}
+"#
+        );
+
+        let json = firefox(Some("{\n  \"zz\": 1\n}\n"), "a@b", "1.0.0", "x", None).unwrap();
+        assert_eq!(
+            json,
+            "{\n  \"zz\": 1,\n  \"addons\": {\n    \"a@b\": {\n      \"updates\": [\n        {\n          \
+             \"update_link\": \"x\",\n          \"version\": \"1.0.0\"\n        }\n      ]\n    }\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn chrome_update_xml() {
+        assert_eq!(
+            chrome("abc", "1.2.0", "https://x/?a=1&b=2", Some("ff")),
+            "<?xml version='1.0' encoding='UTF-8'?>\n\
+             <gupdate xmlns='http://www.google.com/update2/response' protocol='2.0'>\n  \
+             <app appid='abc'>\n    \
+             <updatecheck codebase='https://x/?a=1&amp;b=2' version='1.2.0' hash_sha256='ff' />\n  \
+             </app>\n\
+             </gupdate>\n"
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use once_cell::sync::Lazy;
+
+static VERSION_REGEX: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(r"^(\d+)\.(\d+)\.(\d+)(?:-([0-9A-Za-z-]+(?:\.[0-9A-Za-z-]+)*))?$").unwrap()
+});
+
+/// The levels accepted in place of an explicit version.
+pub const LEVELS: [&str; 7] = [
+    "patch",
+    "minor",
+    "major",
+    "prepatch",
+    "preminor",
+    "premajor",
+    "prerelease",
+];
+
+pub enum Query {
+    Version(Version),
+    Major,
+    Minor,
+    Patch,
+    /// The next major version as a prerelease with the given identifier, e.g. `2.0.0-rc.0`.
+    PreMajor(String),
+    PreMinor(String),
+    PrePatch(String),
+    /// The next prerelease: `1.2.4-rc.0` -> `1.2.4-rc.1`, or `1.2.3` -> `1.2.4-rc.0`.
+    Prerelease(String),
+}
+
+impl Query {
+    /// Parse a level or an explicit version, using `preid` for the prerelease levels.
+    pub fn parse(s: &str, preid: &str) -> Result<Self, String> {
+        Ok(match s {
+            "major" => Query::Major,
+            "minor" => Query::Minor,
+            "patch" => Query::Patch,
+            "premajor" => Query::PreMajor(preid.to_string()),
+            "preminor" => Query::PreMinor(preid.to_string()),
+            "prepatch" => Query::PrePatch(preid.to_string()),
+            "prerelease" => Query::Prerelease(preid.to_string()),
+            x => Query::Version(Version::from_str(x)?),
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Query::Version(_) => "version",
+            Query::Major => "major",
+            Query::Minor => "minor",
+            Query::Patch => "patch",
+            Query::PreMajor(_) => "premajor",
+            Query::PreMinor(_) => "preminor",
+            Query::PrePatch(_) => "prepatch",
+            Query::Prerelease(_) => "prerelease",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    /// Dot-separated prerelease identifiers, empty for a release.
+    pre: Vec<String>,
+    /// Minimum digits of each part, so zero-padded parts (`26.01.05`) keep their padding.
+    width: [usize; 3],
+}
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self::padded([major, minor, patch], [0; 3])
+    }
+
+    pub fn padded(parts: [u64; 3], width: [usize; 3]) -> Self {
+        Self {
+            major: parts[0],
+            minor: parts[1],
+            patch: parts[2],
+            pre: Vec::new(),
+            width,
+        }
+    }
+
+    pub fn from_str(version: &str) -> Result<Self, String> {
+        let v = VERSION_REGEX
+            .captures(version)
+            .ok_or_else(|| "Invalid version format".to_string())?;
+        let part = |i: usize| {
+            v[i].parse::<u64>()
+                .map_err(|_| format!("{} is too large for a version part in {}", &v[i], version))
+        };
+        Ok(Self {
+            major: part(1)?,
+            minor: part(2)?,
+            patch: part(3)?,
+            pre: v
+                .get(4)
+                .map(|pre| pre.as_str().split('.').map(String::from).collect())
+                .unwrap_or_default(),
+            width: [1, 2, 3].map(|i| if v[i].starts_with('0') { v[i].len() } else { 0 }),
+        })
+    }
+
+    /// `major.minor.patch`, without the prerelease.
+    pub fn release(&self) -> (u64, u64, u64) {
+        (self.major, self.minor, self.patch)
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// This version with its prerelease identifiers replaced by `pre`.
+    pub fn with_prerelease(&self, pre: &[&str]) -> Self {
+        Self {
+            pre: pre.iter().map(|id| id.to_string()).collect(),
+            ..self.clone()
+        }
+    }
+
+    pub fn is_version(s: &str) -> bool {
+        VERSION_REGEX.is_match(s)
+    }
+
+    /// Release levels finish a prerelease of that level (`2.0.0-rc.1` -> major -> `2.0.0`)
+    /// instead of skipping past it; the `pre*` levels always start a new prerelease.
+    /// Fails, leaving the version as it was, if a part would overflow.
+    pub fn bump(&mut self, query: Query) -> Result<(), String> {
+        let is_pre = !self.pre.is_empty();
+        let mut next = self.clone();
+        match query {
+            Query::Major => {
+                if !(is_pre && self.minor == 0 && self.patch == 0) {
+                    next.major = increment(self.major)?;
+                }
+                next.minor = 0;
+                next.patch = 0;
+                next.pre.clear();
+            }
+            Query::Minor => {
+                if !(is_pre && self.patch == 0) {
+                    next.minor = increment(self.minor)?;
+                }
+                next.patch = 0;
+                next.pre.clear();
+            }
+            Query::Patch => {
+                if !is_pre {
+                    next.patch = increment(self.patch)?;
+                }
+                next.pre.clear();
+            }
+            Query::PreMajor(id) => {
+                next = Self::padded([increment(self.major)?, 0, 0], self.width);
+                next.pre = vec![id, "0".to_string()];
+            }
+            Query::PreMinor(id) => {
+                next = Self::padded([self.major, increment(self.minor)?, 0], self.width);
+                next.pre = vec![id, "0".to_string()];
+            }
+            Query::PrePatch(id) => {
+                next = Self::padded([self.major, self.minor, increment(self.patch)?], self.width);
+                next.pre = vec![id, "0".to_string()];
+            }
+            Query::Prerelease(id) => match self.pre.as_slice() {
+                [pre_id, n] if *pre_id == id && n.parse::<u64>().is_ok() => {
+                    next.pre[1] = increment(n.parse().unwrap())?.to_string();
+                }
+                _ => {
+                    if !is_pre {
+                        next.patch = increment(self.patch)?;
+                    }
+                    next.pre = vec![id, "0".to_string()];
+                }
+            },
+            Query::Version(v) => next = v,
+        }
+        *self = next;
+        Ok(())
+    }
+
+    /// Fail if a part is over `max`, e.g. 65535 for Chrome extension versions.
+    pub fn check_max(&self, max: u64) -> Result<(), String> {
+        if [self.major, self.minor, self.patch]
+            .iter()
+            .any(|&n| n > max)
+        {
+            return Err(format!(
+                "{} has a part over {}, the largest allowed",
+                self, max
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn increment(n: u64) -> Result<u64, String> {
+    n.checked_add(1)
+        .ok_or_else(|| format!("version part {} cannot be incremented", n))
+}
+
+/// SemVer precedence: a prerelease sorts before its release, and prerelease identifiers
+/// compare numerically when both are numbers, numbers before words, and fewer before more.
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+        self.release().cmp(&other.release()).then_with(|| {
+            match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => {
+                    for (a, b) in self.pre.iter().zip(&other.pre) {
+                        let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+                            (Ok(x), Ok(y)) => x.cmp(&y),
+                            (Ok(_), Err(_)) => Ordering::Less,
+                            (Err(_), Ok(_)) => Ordering::Greater,
+                            (Err(_), Err(_)) => Ordering::Equal,
+                        }
+                        .then_with(|| a.cmp(b));
+                        if ordering != Ordering::Equal {
+                            return ordering;
+                        }
+                    }
+                    self.pre.len().cmp(&other.pre.len())
+                }
+            }
+        })
+    }
+}
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+impl Eq for Version {}
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let [w0, w1, w2] = self.width;
+        write!(
+            f,
+            "{:0w0$}.{:0w1$}.{:0w2$}",
+            self.major, self.minor, self.patch
+        )?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bumped(version: &str, level: &str) -> String {
+        let mut version = Version::from_str(version).unwrap();
+        version.bump(Query::parse(level, "rc").unwrap()).unwrap();
+        version.to_string()
+    }
+
+    #[test]
+    fn replace_prerelease() {
+        let version = Version::from_str("1.3.0-rc.1").unwrap();
+        assert_eq!(
+            version
+                .with_prerelease(&["nightly", "20261017", "abc1234"])
+                .to_string(),
+            "1.3.0-nightly.20261017.abc1234"
+        );
+        assert_eq!(version.with_prerelease(&[]).to_string(), "1.3.0");
+    }
+
+    #[test]
+    fn parse_prerelease() {
+        assert_eq!(
+            Version::from_str("1.2.3-beta.1").unwrap().to_string(),
+            "1.2.3-beta.1"
+        );
+        assert!(Version::from_str("1.2.3-").is_err());
+        assert!(Version::from_str("1.2").is_err());
+    }
+
+    #[test]
+    fn zero_padding_is_kept() {
+        assert_eq!(bumped("26.01.05", "patch"), "26.01.06");
+        assert_eq!(bumped("26.09.05", "minor"), "26.10.00");
+        assert_eq!(
+            Version::from_str("26.01.05").unwrap(),
+            Version::new(26, 1, 5)
+        );
+    }
+
+    #[test]
+    fn large_parts() {
+        assert_eq!(bumped("70000.0.0", "patch"), "70000.0.1");
+        assert!(Version::from_str("99999999999999999999.0.0").is_err());
+        let mut version = Version::from_str(&format!("1.2.{}", u64::MAX)).unwrap();
+        assert!(version.bump(Query::Patch).is_err());
+        assert_eq!(version.to_string(), format!("1.2.{}", u64::MAX));
+        assert!(Version::new(1, 65536, 0).check_max(65535).is_err());
+        assert!(Version::new(1, 65535, 0).check_max(65535).is_ok());
+    }
+
+    #[test]
+    fn semver_precedence() {
+        let versions = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.10.0",
+            "2.0.0",
+        ];
+        for pair in versions.windows(2) {
+            let a = Version::from_str(pair[0]).unwrap();
+            let b = Version::from_str(pair[1]).unwrap();
+            assert!(a < b, "{} < {}", a, b);
+        }
+    }
+
+    #[test]
+    fn bump_release() {
+        assert_eq!(bumped("1.2.3", "patch"), "1.2.4");
+        assert_eq!(bumped("1.2.3", "minor"), "1.3.0");
+        assert_eq!(bumped("1.2.3", "major"), "2.0.0");
+    }
+
+    #[test]
+    fn bump_finishes_prerelease() {
+        assert_eq!(bumped("1.2.4-rc.1", "patch"), "1.2.4");
+        assert_eq!(bumped("1.3.0-rc.1", "minor"), "1.3.0");
+        assert_eq!(bumped("1.2.4-rc.1", "minor"), "1.3.0");
+        assert_eq!(bumped("2.0.0-rc.1", "major"), "2.0.0");
+    }
+
+    #[test]
+    fn bump_prerelease() {
+        assert_eq!(bumped("1.2.3", "prepatch"), "1.2.4-rc.0");
+        assert_eq!(bumped("1.2.3", "preminor"), "1.3.0-rc.0");
+        assert_eq!(bumped("1.2.3", "premajor"), "2.0.0-rc.0");
+        assert_eq!(bumped("1.2.3", "prerelease"), "1.2.4-rc.0");
+        assert_eq!(bumped("1.2.4-rc.0", "prerelease"), "1.2.4-rc.1");
+        assert_eq!(bumped("1.2.4-beta.3", "prerelease"), "1.2.4-rc.0");
+        assert_eq!(bumped("2.0.0-rc.1", "premajor"), "3.0.0-rc.0");
+        assert_eq!(bumped("1.2.4-rc.1", "prepatch"), "1.2.5-rc.0");
+    }
+}
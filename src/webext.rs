@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Chrome rejects extension names longer than this.
+const NAME_MAX: usize = 75;
+/// Longest `version_name` accepted; it is shown in place of the version in the browser.
+const VERSION_NAME_MAX: usize = 64;
+/// Chrome and Firefox reject version parts larger than this.
+pub const VERSION_PART_MAX: u64 = 65535;
+
+/// What to do with the problems found in a WebExtension manifest.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Validation {
+    #[default]
+    Off,
+    Warn,
+    Error,
+}
+impl Validation {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "off" => Ok(Validation::Off),
+            "warn" => Ok(Validation::Warn),
+            "error" => Ok(Validation::Error),
+            _ => Err(format!("invalid validate mode `{}` (off, warn, error)", s)),
+        }
+    }
+}
+
+/// Whether `path` looks like an extension manifest: `manifest.json` or a variant such as
+/// `manifest.firefox.json`.
+pub fn is_manifest(path: &str) -> bool {
+    let name = std::path::Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    name.starts_with("manifest.") && name.ends_with(".json")
+}
+
+/// Check an extension version: one to four dot-separated integers, each at most 65535 and
+/// without leading zeros.
+pub fn check_version(version: &str) -> Result<(), String> {
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.len() > 4 {
+        return Err(format!("version `{}` has more than 4 parts", version));
+    }
+    for part in parts {
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!(
+                "version `{}` must be dot-separated integers",
+                version
+            ));
+        }
+        if part.len() > 1 && part.starts_with('0') {
+            return Err(format!(
+                "version `{}` has a part with a leading zero",
+                version
+            ));
+        }
+        if part.parse::<u64>().map_or(true, |n| n > VERSION_PART_MAX) {
+            return Err(format!(
+                "version `{}` has a part over {}",
+                version, VERSION_PART_MAX
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The problems with the extension manifest `json`.
+pub fn validate(json: &str) -> Vec<String> {
+    let manifest: Value = match serde_json::from_str(json) {
+        Ok(manifest) => manifest,
+        Err(e) => return vec![format!("not valid JSON: {}", e)],
+    };
+    let mut problems = Vec::new();
+    match manifest.get("manifest_version").and_then(Value::as_u64) {
+        Some(2) | Some(3) => {}
+        Some(n) => problems.push(format!("unsupported manifest_version {}", n)),
+        None => problems.push("missing manifest_version".to_string()),
+    }
+    match manifest.get("name").and_then(Value::as_str) {
+        Some("") | None => problems.push("missing name".to_string()),
+        Some(name) if name.chars().count() > NAME_MAX => {
+            problems.push(format!("name is longer than {} characters", NAME_MAX))
+        }
+        Some(_) => {}
+    }
+    match manifest.get("version").and_then(Value::as_str) {
+        Some(version) => {
+            if let Err(e) = check_version(version) {
+                problems.push(e);
+            }
+        }
+        None => problems.push("missing version".to_string()),
+    }
+    if let Some(version_name) = manifest.get("version_name").and_then(Value::as_str) {
+        if version_name.chars().count() > VERSION_NAME_MAX {
+            problems.push(format!(
+                "version_name is longer than {} characters",
+                VERSION_NAME_MAX
+            ));
+        }
+    }
+    problems
+}
+
+/// The Firefox add-on ID of the manifest `json`, from `browser_specific_settings.gecko.id`
+/// (or the older `applications.gecko.id`).
+pub fn gecko_id(json: &str) -> Option<String> {
+    let manifest: Value = serde_json::from_str(json).ok()?;
+    ["browser_specific_settings", "applications"]
+        .iter()
+        .find_map(|key| manifest.get(key)?.get("gecko")?.get("id")?.as_str())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_versions() {
+        assert!(check_version("1").is_ok());
+        assert!(check_version("1.2.3.4").is_ok());
+        assert!(check_version("0.65535.0").is_ok());
+        assert!(check_version("1.2.3.4.5").is_err());
+        assert!(check_version("1.02.3").is_err());
+        assert!(check_version("1.65536").is_err());
+        assert!(check_version("1.2.3-rc.1").is_err());
+        assert!(check_version("1..2").is_err());
+    }
+
+    #[test]
+    fn manifest_problems() {
+        assert!(validate(r#"{"manifest_version":3,"name":"x","version":"1.0"}"#).is_empty());
+        assert_eq!(
+            validate(r#"{"name":"","version":"1.0.0-rc.0"}"#),
+            vec![
+                "missing manifest_version",
+                "missing name",
+                "version `1.0.0-rc.0` must be dot-separated integers"
+            ]
+        );
+        let long_name = format!(
+            r#"{{"manifest_version":2,"name":"x","version":"1","version_name":"{}"}}"#,
+            "a".repeat(65)
+        );
+        assert_eq!(
+            validate(&long_name),
+            vec!["version_name is longer than 64 characters"]
+        );
+    }
+
+    #[test]
+    fn manifest_paths() {
+        assert!(is_manifest("manifest.json"));
+        assert!(is_manifest("ext/manifest.firefox.json"));
+        assert!(!is_manifest("package.json"));
+    }
+
+    #[test]
+    fn gecko_ids() {
+        assert_eq!(
+            gecko_id(r#"{"browser_specific_settings":{"gecko":{"id":"a@b.c"}}}"#).as_deref(),
+            Some("a@b.c")
+        );
+        assert_eq!(
+            gecko_id(r#"{"applications":{"gecko":{"id":"x@y"}}}"#).as_deref(),
+            Some("x@y")
+        );
+        assert_eq!(gecko_id(r#"{"name":"x"}"#), None);
+    }
+}
@@ -0,0 +1,142 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::{glob_match, Target, Workspace};
+
+/// A package of a workspace: a directory holding its own versioned files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Package {
+    pub name: String,
+    pub dir: PathBuf,
+}
+impl Package {
+    /// The package's targets, with paths relative to the current directory.
+    pub fn targets(&self, files: &[Target]) -> Vec<Target> {
+        files
+            .iter()
+            .map(|target| Target {
+                path: self.dir.join(&target.path).to_string_lossy().into_owned(),
+                ..target.clone()
+            })
+            .collect()
+    }
+}
+
+/// Directories matching `pattern`, where `*` in a path segment matches any name.
+fn expand(pattern: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::new()];
+    for component in Path::new(pattern).components() {
+        let segment = component.as_os_str().to_string_lossy();
+        if !matches!(component, Component::Normal(_)) || !segment.contains('*') {
+            dirs = dirs.into_iter().map(|dir| dir.join(&*segment)).collect();
+            continue;
+        }
+        let mut matched = Vec::new();
+        for dir in dirs {
+            let entries = match std::fs::read_dir(if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                &dir
+            }) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if entry.path().is_dir() && glob_match(&segment, &name) {
+                    matched.push(dir.join(name));
+                }
+            }
+        }
+        dirs = matched;
+    }
+    dirs.into_iter().filter(|dir| dir.is_dir()).collect()
+}
+
+/// Find the packages of `workspace`: directories matching its globs that contain its
+/// first file. Packages are named after their directory and sorted by name.
+pub fn discover(workspace: &Workspace) -> Result<Vec<Package>, String> {
+    let marker = workspace
+        .files
+        .first()
+        .ok_or_else(|| "workspace `files` must list at least one file".to_string())?;
+    let mut packages: Vec<Package> = Vec::new();
+    for pattern in &workspace.packages {
+        for dir in expand(pattern) {
+            if !dir.join(&marker.path).is_file() {
+                continue;
+            }
+            let name = match dir.file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => continue,
+            };
+            if let Some(other) = packages.iter().find(|p| p.name == name) {
+                if other.dir == dir {
+                    continue;
+                }
+                return Err(format!(
+                    "package name `{}` is used by both {} and {}",
+                    name,
+                    other.dir.display(),
+                    dir.display()
+                ));
+            }
+            packages.push(Package { name, dir });
+        }
+    }
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packages)
+}
+
+/// Keep only the packages named in `names` (all of them when `names` is empty).
+pub fn select(packages: Vec<Package>, names: &[&str]) -> Result<Vec<Package>, String> {
+    if names.is_empty() {
+        return Ok(packages);
+    }
+    if let Some(unknown) = names
+        .iter()
+        .find(|&&n| !packages.iter().any(|p| p.name == n))
+    {
+        let known: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        return Err(format!(
+            "unknown package `{}` (packages: {})",
+            unknown,
+            known.join(", ")
+        ));
+    }
+    Ok(packages
+        .into_iter()
+        .filter(|p| names.contains(&p.name.as_str()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str) -> Package {
+        Package {
+            name: name.to_string(),
+            dir: Path::new("packages").join(name),
+        }
+    }
+
+    #[test]
+    fn select_subset() {
+        let packages = vec![package("options"), package("popup")];
+        assert_eq!(
+            select(packages.clone(), &["popup"]).unwrap(),
+            vec![package("popup")]
+        );
+        assert_eq!(select(packages.clone(), &[]).unwrap(), packages);
+        assert!(select(packages, &["nope"]).is_err());
+    }
+
+    #[test]
+    fn package_targets() {
+        let files = vec![Target::new("manifest.json")];
+        assert_eq!(
+            package("popup").targets(&files)[0].path,
+            Path::new("packages/popup/manifest.json").to_string_lossy()
+        );
+    }
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn auto_minor_from_feat() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+    git(tmp.path(), &["tag", "v1.2.3"]);
+    empty_commit(tmp.path(), "fix: first");
+    empty_commit(tmp.path(), "feat: second");
+
+    let output = bump(tmp.path(), &["auto"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("minor bump since v1.2.3"));
+    assert!(stdout.contains("feat: second"));
+    assert!(!stdout.contains("fix: first"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.3.0\""));
+}
+
+#[test]
+fn auto_major_from_breaking_change() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+    git(tmp.path(), &["tag", "v1.2.3"]);
+    empty_commit(tmp.path(), "feat: a\n\nBREAKING CHANGE: b");
+
+    let output = bump(tmp.path(), &["auto"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"2.0.0\""));
+}
+
+#[test]
+fn auto_refuses_without_commits() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+    git(tmp.path(), &["tag", "v1.2.3"]);
+
+    let output = bump(tmp.path(), &["auto"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No releasable commits since v1.2.3"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.3\""));
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::*;
+
+const APP_JSON: &str = r#"{
+  "expo": {
+    "version": "1.2.3",
+    "android": { "versionCode": 41 },
+    "ios": { "buildNumber": "10203" }
+  }
+}
+"#;
+
+const CONFIG: &str = r#"
+[[files]]
+path = "app.json"
+key = "expo.version"
+
+[[files.build]]
+key = "expo.android.versionCode"
+
+[[files.build]]
+key = "expo.ios.buildNumber"
+strategy = "semver"
+"#;
+
+#[test]
+fn build_numbers_are_rewritten_with_the_version() {
+    let tmp = TempDir::new();
+    std::fs::write(tmp.path().join("app.json"), APP_JSON).unwrap();
+    std::fs::write(tmp.path().join(".manifest-bump.toml"), CONFIG).unwrap();
+
+    let output = bump(tmp.path(), &["minor"]);
+    assert!(output.status.success());
+    assert_eq!(
+        read(tmp.path(), "app.json").trim_end(),
+        APP_JSON
+            .replace("1.2.3", "1.3.0")
+            .replace("41", "42")
+            .replace("10203", "10300")
+            .trim_end()
+    );
+}
+
+#[test]
+fn semver_build_number_must_fit() {
+    let tmp = TempDir::new();
+    std::fs::write(tmp.path().join("app.json"), APP_JSON).unwrap();
+    std::fs::write(tmp.path().join(".manifest-bump.toml"), CONFIG).unwrap();
+
+    let output = bump(tmp.path(), &["1.100.0"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be encoded"));
+    assert_eq!(read(tmp.path(), "app.json"), APP_JSON);
+}
+
+#[test]
+fn semver_build_number_is_shared_by_prereleases() {
+    let tmp = TempDir::new();
+    std::fs::write(tmp.path().join("app.json"), APP_JSON).unwrap();
+    std::fs::write(tmp.path().join(".manifest-bump.toml"), CONFIG).unwrap();
+
+    for (level, version, code) in [
+        ("prepatch", "1.2.4-rc.0", "10204"),
+        ("prerelease", "1.2.4-rc.1", "10204"),
+        ("patch", "1.2.4", "10204"),
+    ] {
+        let output = bump(tmp.path(), &[level]);
+        assert!(output.status.success(), "{}", level);
+        let app = read(tmp.path(), "app.json");
+        assert!(app.contains(&format!("\"version\": \"{}\"", version)));
+        assert!(app.contains(&format!("\"buildNumber\": \"{}\"", code)));
+    }
+
+    let output = bump(tmp.path(), &["patch"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "app.json").contains("\"buildNumber\": \"10205\""));
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::*;
+
+const OCT_18: &str = "2026-10-18";
+
+#[test]
+fn micro_counts_releases_within_the_month() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "2026.9.3");
+
+    let output = bump_at(tmp.path(), OCT_18, &["--calver", "YYYY.MM.MICRO"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"2026.10.0\""));
+
+    let output = bump_at(tmp.path(), "2026-10-19", &["--calver", "YYYY.MM.MICRO"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"2026.10.1\""));
+}
+
+#[test]
+fn zero_padded_month_from_config() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "26.09.30");
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "calver = \"YY.0M.DD\"\n",
+    )
+    .unwrap();
+
+    let output = bump_at(tmp.path(), OCT_18, &[]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"26.10.18\""));
+
+    let output = bump_at(tmp.path(), OCT_18, &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already today's version"));
+}
+
+#[test]
+fn source_date_epoch_is_not_today() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "2026.9.3");
+
+    let output = bump_with_env(
+        tmp.path(),
+        &[("SOURCE_DATE_EPOCH", "0")],
+        &["--calver", "YYYY.MM.MICRO"],
+    );
+    assert!(output.status.success());
+    assert!(!read(tmp.path(), "manifest.json").contains("\"1970."));
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn changelog_is_written_and_committed() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    git(tmp.path(), &["tag", "v0.1.0"]);
+    empty_commit(tmp.path(), "feat: add thing");
+    empty_commit(tmp.path(), "fix: broken thing");
+
+    let output = bump(tmp.path(), &["minor", "-g", "--changelog"]);
+    assert!(output.status.success());
+
+    let changelog = read(tmp.path(), "CHANGELOG.md");
+    assert!(changelog.starts_with("# Changelog\n"));
+    assert!(changelog.contains("\n## v0.2.0 - "));
+    assert!(changelog.contains("### Added\n\n- add thing ("));
+    assert!(changelog.contains("### Fixed\n\n- broken thing ("));
+    assert_eq!(
+        git(tmp.path(), &["show", "--name-only", "--format=", "HEAD"]),
+        "CHANGELOG.md\nmanifest.json\n"
+    );
+}
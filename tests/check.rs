@@ -0,0 +1,38 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn exit_status_tells_whether_manifest_satisfies_range() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.4.2");
+
+    let output = bump(tmp.path(), &["check", "--satisfies", "^1.4"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "1.4.2 satisfies ^1.4\n"
+    );
+
+    let output = bump(tmp.path(), &["check", "--satisfies", ">=2.1.0"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "1.4.2 does not satisfy >=2.1.0\n"
+    );
+}
+
+#[test]
+fn explicit_version_and_invalid_range() {
+    let tmp = TempDir::new();
+
+    let output = bump(
+        tmp.path(),
+        &["check", "2.0.0-rc.1", "--satisfies", "^1.4 || >=2.0.0-rc.0"],
+    );
+    assert!(output.status.success());
+
+    let output = bump(tmp.path(), &["check", "1.0.0", "--satisfies", "=>1"]);
+    assert!(!output.status.success());
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,143 @@
+#![allow(dead_code)]
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+use uuid::Uuid;
+
+/// A scratch directory under the system temp dir, removed on drop.
+pub struct TempDir(PathBuf);
+impl TempDir {
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("manifest-bump-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Apply an isolated git identity/config so tests do not depend on the host setup.
+fn isolate<'a>(command: &'a mut Command, home: &Path) -> &'a mut Command {
+    command
+        .env("HOME", home)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_AUTHOR_NAME", "test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .env_remove("GITHUB_OUTPUT")
+        .env_remove("MANIFEST_BUMP_TODAY")
+}
+
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = isolate(&mut Command::new("git"), dir)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+pub fn bump(dir: &Path, args: &[&str]) -> Output {
+    isolate(&mut Command::new(env!("CARGO_BIN_EXE_manifest-bump")), dir)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+/// Run the binary with "today" fixed to `date` (`YYYY-MM-DD`).
+pub fn bump_at(dir: &Path, date: &str, args: &[&str]) -> Output {
+    isolate(&mut Command::new(env!("CARGO_BIN_EXE_manifest-bump")), dir)
+        .env("MANIFEST_BUMP_TODAY", date)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+/// Run the binary with the extra environment variables `vars`.
+pub fn bump_with_env(dir: &Path, vars: &[(&str, &str)], args: &[&str]) -> Output {
+    isolate(&mut Command::new(env!("CARGO_BIN_EXE_manifest-bump")), dir)
+        .envs(vars.iter().copied())
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+/// Run the binary with `input` piped to its stdin.
+pub fn bump_with_input(dir: &Path, args: &[&str], input: &str) -> Output {
+    let mut child = isolate(&mut Command::new(env!("CARGO_BIN_EXE_manifest-bump")), dir)
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // A run rejected before reading stdin closes the pipe early, which is not an error here.
+    let written = child.stdin.take().unwrap().write_all(input.as_bytes());
+    if let Err(e) = written {
+        assert_eq!(e.kind(), std::io::ErrorKind::BrokenPipe);
+    }
+    child.wait_with_output().unwrap()
+}
+
+/// Create a repository with an initial commit of `manifest.json` at `version`.
+pub fn init_repo(dir: &Path, version: &str) {
+    git(dir, &["init", "-q", "-b", "main"]);
+    write_manifest(dir, version);
+    git(dir, &["add", "manifest.json"]);
+    git(dir, &["commit", "-q", "-m", "initial"]);
+}
+
+pub fn empty_commit(dir: &Path, message: &str) {
+    git(dir, &["commit", "-q", "--allow-empty", "-m", message]);
+}
+
+pub fn write_manifest(dir: &Path, version: &str) {
+    std::fs::write(
+        dir.join("manifest.json"),
+        format!(
+            "{{\n  \"name\": \"test\",\n  \"version\": \"{}\"\n}}\n",
+            version
+        ),
+    )
+    .unwrap();
+}
+
+pub fn read(dir: &Path, file: &str) -> String {
+    std::fs::read_to_string(dir.join(file)).unwrap()
+}
+
+/// Create a bare repository in `dir/remote.git` and register it as `origin` of `dir/work`.
+pub fn init_with_remote(dir: &Path, version: &str) -> (PathBuf, PathBuf) {
+    let remote = dir.join("remote.git");
+    let work = dir.join("work");
+    std::fs::create_dir_all(&remote).unwrap();
+    std::fs::create_dir_all(&work).unwrap();
+    git(&remote, &["init", "-q", "--bare", "-b", "main"]);
+    init_repo(&work, version);
+    git(
+        &work,
+        &["remote", "add", "origin", remote.to_str().unwrap()],
+    );
+    git(&work, &["push", "-q", "origin", "main"]);
+    (work, remote)
+}
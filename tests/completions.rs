@@ -0,0 +1,66 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn completions_offer_levels_and_packages() {
+    let tmp = TempDir::new();
+    for name in ["popup", "options"] {
+        let dir = tmp.path().join("packages").join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("manifest.json"), "{\"version\": \"1.0.0\"}\n").unwrap();
+    }
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "[workspace]\npackages = [\"packages/*\"]\n",
+    )
+    .unwrap();
+
+    let output = bump(tmp.path(), &["completions", "bash"]);
+    assert!(output.status.success());
+    let bash = String::from_utf8(output.stdout).unwrap();
+    assert!(bash.contains(" auto patch minor major prepatch preminor premajor prerelease "));
+    assert!(bash.contains("compgen -W \"options popup\""));
+
+    let output = bump(tmp.path(), &["completions", "zsh"]);
+    let zsh = String::from_utf8(output.stdout).unwrap();
+    assert!(zsh.contains("'::version:(auto patch minor major"));
+    assert!(zsh.contains(": :(options popup)"));
+
+    for shell in ["fish", "elvish", "powershell"] {
+        let output = bump(tmp.path(), &["completions", shell]);
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("prerelease"));
+    }
+}
+
+#[test]
+fn calver_levels_are_offered() {
+    let tmp = TempDir::new();
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "calver = \"YYYY.MM.MICRO\"\n",
+    )
+    .unwrap();
+
+    let output = bump(tmp.path(), &["completions", "fish"]);
+    assert!(output.status.success());
+    let fish = String::from_utf8(output.stdout).unwrap();
+    assert!(fish.contains("-a \"patch\" -d 'version level'"));
+    assert!(!fish.contains("auto"));
+}
+
+#[test]
+fn man_page() {
+    let tmp = TempDir::new();
+    let output = bump(tmp.path(), &["man"]);
+    assert!(output.status.success());
+    let page = String::from_utf8(output.stdout).unwrap();
+    assert!(page.starts_with(".TH MANIFEST-BUMP 1 "));
+    assert!(page.contains("\n.SH NAME\nmanifest\\-bump \\- Bump the version of a manifest.json\n"));
+    assert!(page.contains("--from-tag"));
+    assert!(page.contains("\n.SS manifest\\-bump snapshot\n"));
+    assert!(page.contains("manifest-bump snapshot [FLAGS] [OPTIONS] [level]"));
+}
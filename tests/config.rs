@@ -0,0 +1,133 @@
+mod common;
+
+use common::*;
+
+const PACKAGE_JSON: &str = "{\n  \"name\": \"test\",\n  \"version\": \"0.1.0\"\n}\n";
+
+#[test]
+fn config_file_drives_the_bump() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    std::fs::write(tmp.path().join("package.json"), PACKAGE_JSON).unwrap();
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        r#"
+tag = "release-{new}"
+message = "release {old} -> {new}"
+git = true
+
+[[files]]
+path = "manifest.json"
+
+[[files]]
+path = "package.json"
+"#,
+    )
+    .unwrap();
+    git(tmp.path(), &["add", "."]);
+    git(tmp.path(), &["commit", "-q", "-m", "config"]);
+
+    let output = bump(tmp.path(), &["minor"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.2.0\""));
+    assert!(read(tmp.path(), "package.json").contains("\"0.2.0\""));
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "release-0.2.0\n");
+    assert_eq!(
+        git(tmp.path(), &["log", "-1", "--format=%s"]),
+        "release 0.1.0 -> 0.2.0\n"
+    );
+}
+
+#[test]
+fn cli_flags_override_config() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    std::fs::write(tmp.path().join("other.json"), PACKAGE_JSON).unwrap();
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "git = true\nlevel = \"major\"\n",
+    )
+    .unwrap();
+
+    let output = bump(tmp.path(), &["-f", "other.json", "--no-git"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "other.json").contains("\"1.0.0\""));
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.1.0\""));
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "");
+}
+
+#[test]
+fn config_embedded_in_package_json_and_found_from_subdirectory() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    std::fs::write(
+        tmp.path().join("package.json"),
+        r#"{"name": "test", "manifest-bump": {"files": [{"path": "manifest.json"}], "level": "minor"}}"#,
+    )
+    .unwrap();
+    let sub = tmp.path().join("src");
+    std::fs::create_dir_all(&sub).unwrap();
+
+    let output = bump(&sub, &[]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.2.0\""));
+}
+
+#[test]
+fn refuses_outside_allowed_branches() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "allowed-branches = [\"release/*\"]\n",
+    )
+    .unwrap();
+
+    let output = bump(tmp.path(), &["-g"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("branch `main` is not in allowed-branches"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.1.0\""));
+}
+
+#[test]
+fn git_options_require_git() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "0.1.0");
+
+    let output = bump(tmp.path(), &["--push"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--push requires --git"));
+
+    let output = bump(tmp.path(), &["patch", "--rollback-on-failure"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--rollback-on-failure requires --git")
+    );
+
+    let output = bump(tmp.path(), &["patch", "-g", "--rollback-on-failure"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--rollback-on-failure requires --push")
+    );
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.1.0\""));
+}
+
+#[test]
+fn config_show_prints_effective_settings() {
+    let tmp = TempDir::new();
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "tag = \"v{new}\"\nsign = true\n\n[hooks]\npre-bump = [\"npm test\"]\n",
+    )
+    .unwrap();
+
+    let output = bump(tmp.path(), &["config", "show"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("# .manifest-bump.toml\n"));
+    assert!(stdout.contains("sign = true\n"));
+    assert!(stdout.contains("git = false\n"));
+    assert!(stdout.contains("[[files]]\npath = \"manifest.json\"\nkey = \"version\"\n"));
+    assert!(stdout.contains("pre-bump = [\"npm test\"]"));
+}
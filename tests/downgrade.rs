@@ -0,0 +1,36 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn explicit_lower_version_is_rejected() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.1.0");
+
+    let output = bump(tmp.path(), &["1.0.9"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("would go down from 1.1.0 to 1.0.9"));
+    assert!(stderr.contains("--allow-downgrade"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.1.0\""));
+
+    let output = bump(tmp.path(), &["1.0.9", "--allow-downgrade"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.0.9\""));
+}
+
+#[test]
+fn explicit_same_version_is_rejected() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.1.0");
+
+    let output = bump(tmp.path(), &["1.1.0"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already at 1.1.0"));
+
+    let output = bump(tmp.path(), &["1.1.0", "--allow-same"]);
+    assert!(output.status.success());
+
+    let output = bump(tmp.path(), &["1.1.0-rc.1"]);
+    assert!(!output.status.success());
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn warns_when_manifest_and_tag_disagree() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    git(tmp.path(), &["tag", "v0.3.0"]);
+
+    let output = bump(tmp.path(), &[]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("highest tag reachable from HEAD is v0.3.0"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.1.1\""));
+}
+
+#[test]
+fn bumps_from_highest_tag() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    git(tmp.path(), &["tag", "v0.2.0"]);
+    git(tmp.path(), &["tag", "v0.10.0"]);
+    git(tmp.path(), &["tag", "vnext"]);
+
+    let output = bump(tmp.path(), &["minor", "--from-tag"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.11.0\""));
+}
+
+#[test]
+fn warns_when_new_tag_exists() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    git(tmp.path(), &["tag", "v0.1.0"]);
+    empty_commit(tmp.path(), "fix: x");
+    git(tmp.path(), &["tag", "v0.1.1"]);
+    git(tmp.path(), &["reset", "-q", "--hard", "HEAD~1"]);
+
+    let output = bump(tmp.path(), &[]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("tag v0.1.1 already exists"));
+}
+
+#[test]
+fn existing_tag_is_an_error_with_git() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    git(tmp.path(), &["tag", "v0.1.1"]);
+    let head = git(tmp.path(), &["rev-parse", "HEAD"]);
+
+    let output = bump(tmp.path(), &["-g"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("tag v0.1.1 already exists"));
+    assert_eq!(git(tmp.path(), &["rev-parse", "HEAD"]), head);
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.1.0\""));
+}
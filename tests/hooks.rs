@@ -0,0 +1,188 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn hooks_run_in_order() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+
+    let output = bump(
+        tmp.path(),
+        &[
+            "-g",
+            "--pre-bump",
+            "echo pre-bump >> log",
+            "--post-bump",
+            "echo post-bump >> log",
+            "--run",
+            "echo run >> log",
+            "--pre-commit",
+            "echo pre-commit >> log",
+            "--post-tag",
+            "echo post-tag >> log",
+            "--post-tag",
+            "echo post-tag-2 >> log",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        read(tmp.path(), "log"),
+        "pre-bump\npost-bump\nrun\npre-commit\npost-tag\npost-tag-2\n"
+    );
+}
+
+#[test]
+fn failing_run_restores_files() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    let manifest = read(tmp.path(), "manifest.json");
+
+    let output = bump(tmp.path(), &["--changelog", "-r", "exit 3"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("post-bump hook `exit 3` failed"));
+    assert_eq!(read(tmp.path(), "manifest.json"), manifest);
+    assert!(!tmp.path().join("CHANGELOG.md").exists());
+}
+
+#[test]
+fn failing_pre_bump_writes_nothing() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    let manifest = read(tmp.path(), "manifest.json");
+
+    let output = bump(
+        tmp.path(),
+        &["--pre-bump", "false", "--post-bump", "touch ran"],
+    );
+    assert!(!output.status.success());
+    assert_eq!(read(tmp.path(), "manifest.json"), manifest);
+    assert!(!tmp.path().join("ran").exists());
+}
+
+#[test]
+fn failing_post_tag_undoes_commit_and_tag() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    let head = git(tmp.path(), &["rev-parse", "HEAD"]);
+
+    let output = bump(tmp.path(), &["-g", "--post-tag", "false"]);
+    assert!(!output.status.success());
+    assert_eq!(git(tmp.path(), &["rev-parse", "HEAD"]), head);
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "");
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.1.0\""));
+}
+
+#[test]
+fn stage_hook_changes() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+
+    let output = bump(
+        tmp.path(),
+        &[
+            "-g",
+            "--stage-hook-changes",
+            "--post-bump",
+            "mkdir -p dist && echo bundle > dist/bundle.js",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        git(tmp.path(), &["show", "--name-only", "--format=", "HEAD"]),
+        "dist/bundle.js\nmanifest.json\n"
+    );
+}
+
+#[test]
+fn hooks_receive_version_information() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+
+    let output = bump(
+        tmp.path(),
+        &[
+            "minor",
+            "-r",
+            "echo \"$MANIFEST_BUMP_OLD_VERSION $MANIFEST_BUMP_NEW_VERSION $MANIFEST_BUMP_FILE $MANIFEST_BUMP_LEVEL $MANIFEST_BUMP_TAG $MANIFEST_BUMP_STAGE\" > env",
+            "--post-bump",
+            "echo {old} {new} {file} {level} {tag} > placeholders",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        read(tmp.path(), "env"),
+        "0.1.0 0.2.0 manifest.json minor v0.2.0 post-bump\n"
+    );
+    assert_eq!(
+        read(tmp.path(), "placeholders"),
+        "0.1.0 0.2.0 manifest.json minor v0.2.0\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn failed_commit_keeps_earlier_commits() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    std::fs::write(tmp.path().join("notes.txt"), "mine\n").unwrap();
+    git(tmp.path(), &["add", "notes.txt"]);
+    git(tmp.path(), &["commit", "-q", "-m", "unrelated"]);
+    let head = git(tmp.path(), &["rev-parse", "HEAD"]);
+    let hook = tmp.path().join(".git/hooks/pre-commit");
+    std::fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = bump(tmp.path(), &["minor", "-g", "--post-tag", "false"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to commit"));
+    assert_eq!(git(tmp.path(), &["rev-parse", "HEAD"]), head);
+    assert_eq!(read(tmp.path(), "notes.txt"), "mine\n");
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "");
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.1.0\""));
+}
+
+#[cfg(unix)]
+#[test]
+fn placeholders_are_quoted() {
+    let tmp = TempDir::new();
+    let dir = tmp.path().join("my $dir");
+    std::fs::create_dir(&dir).unwrap();
+    write_manifest(&dir, "0.1.0");
+
+    let output = bump(
+        tmp.path(),
+        &[
+            "minor",
+            "-f",
+            "my $dir/manifest.json",
+            "--post-bump",
+            "cp {file} copied.json",
+        ],
+    );
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "copied.json").contains("\"0.2.0\""));
+}
+
+#[cfg(unix)]
+#[test]
+fn placeholders_in_values_are_not_expanded() {
+    let tmp = TempDir::new();
+    let dir = tmp.path().join("{new} dir");
+    std::fs::create_dir(&dir).unwrap();
+    write_manifest(&dir, "0.1.0");
+
+    let output = bump(
+        tmp.path(),
+        &[
+            "minor",
+            "-f",
+            "{new} dir/manifest.json",
+            "--post-bump",
+            "echo {file} > file",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(read(tmp.path(), "file"), "{new} dir/manifest.json\n");
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn pick_level_from_menu_and_confirm() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+
+    let output = bump_with_input(tmp.path(), &["-i", "--git"], "2\ny\n");
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("current version 1.2.3"));
+    assert!(stderr.contains("7) prerelease  1.2.4-rc.0"));
+    assert!(stderr.contains("-  \"version\": \"1.2.3\"\n+  \"version\": \"1.3.0\""));
+    assert!(stderr.contains("git tag v1.3.0"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.3.0\""));
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "v1.3.0\n");
+}
+
+#[test]
+fn custom_prerelease_version() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+
+    let output = bump_with_input(tmp.path(), &["-i", "--preid", "beta"], "4\ny\n");
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.4-beta.0\""));
+
+    let output = bump_with_input(tmp.path(), &["-i"], "8\n2.0.0\ny\n");
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"2.0.0\""));
+}
+
+#[test]
+fn declining_changes_nothing() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+
+    let output = bump_with_input(tmp.path(), &["-i", "--git"], "1\nn\n");
+    assert!(!output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.3\""));
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "");
+}
+
+#[test]
+fn no_answers_on_stdin() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+
+    let output = bump_with_input(tmp.path(), &["-i"], "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not a terminal"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.3\""));
+}
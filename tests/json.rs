@@ -0,0 +1,157 @@
+mod common;
+
+use serde_json::Value;
+
+use common::*;
+
+fn report(output: &std::process::Output) -> Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn json_report_of_a_bump() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+
+    let output = bump(
+        tmp.path(),
+        &["minor", "-g", "--json", "--post-bump", "echo built"],
+    );
+    assert!(output.status.success());
+    let report = report(&output);
+    assert_eq!(report["ok"], true);
+    assert_eq!(
+        report["files"],
+        serde_json::json!([{
+            "path": "manifest.json",
+            "key": "version",
+            "package": null,
+            "old_version": "1.2.3",
+            "new_version": "1.3.0",
+        }])
+    );
+    assert_eq!(report["hooks"][0]["stage"], "post-bump");
+    assert_eq!(report["hooks"][0]["command"], "echo built");
+    assert_eq!(report["hooks"][0]["exit_code"], 0);
+    assert!(report["hooks"][0]["duration_ms"].is_u64());
+    assert_eq!(
+        report["commits"][0],
+        git(tmp.path(), &["rev-parse", "HEAD"]).trim()
+    );
+    assert_eq!(report["tags"], serde_json::json!(["v1.3.0"]));
+    assert_eq!(report["error"], Value::Null);
+}
+
+#[test]
+fn json_report_of_a_failure() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+
+    let output = bump(
+        tmp.path(),
+        &["patch", "-g", "--json", "--pre-commit", "exit 3"],
+    );
+    assert!(!output.status.success());
+    let report = report(&output);
+    assert_eq!(report["ok"], false);
+    assert_eq!(report["hooks"][0]["exit_code"], 3);
+    assert_eq!(
+        report["files"],
+        serde_json::json!([{
+            "path": "manifest.json",
+            "key": "version",
+            "package": null,
+            "old_version": "1.2.3",
+            "new_version": "1.2.4",
+        }])
+    );
+    assert!(report["error"]
+        .as_str()
+        .unwrap()
+        .contains("pre-commit hook `exit 3` failed"));
+    assert_eq!(report["commits"], serde_json::json!([]));
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.3\""));
+
+    git(tmp.path(), &["tag", "v1.2.4"]);
+    let output = bump(tmp.path(), &["patch", "-g", "--json"]);
+    assert!(!output.status.success());
+    let tag_exists = self::report(&output);
+    assert_eq!(
+        tag_exists["error"],
+        "Failed to bump: tag v1.2.4 already exists"
+    );
+    assert_eq!(tag_exists["files"][0]["new_version"], "1.2.4");
+
+    let output = bump(tmp.path(), &["1.0.0", "--json"]);
+    assert!(!output.status.success());
+    let downgrade = self::report(&output);
+    assert_eq!(downgrade["files"][0]["old_version"], "1.2.3");
+    assert_eq!(downgrade["files"][0]["new_version"], Value::Null);
+}
+
+#[test]
+fn json_report_collects_warnings() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.2.3");
+
+    let output = bump(tmp.path(), &["patch", "--validate=warn", "--json"]);
+    assert!(output.status.success());
+    assert_eq!(
+        report(&output)["warnings"][0],
+        "manifest.json: missing manifest_version"
+    );
+}
+
+#[test]
+fn json_output_of_subcommands() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+
+    let output = bump(tmp.path(), &["check", "--satisfies", ">=2", "--json"]);
+    assert_eq!(output.status.code(), Some(1));
+    let check = report(&output);
+    assert_eq!(check["ok"], true);
+    assert_eq!(
+        check["output"],
+        serde_json::json!({ "version": "1.2.3", "range": ">=2", "satisfies": false })
+    );
+
+    let output = bump(tmp.path(), &["--json", "config", "show"]);
+    assert!(output.status.success());
+    assert_eq!(report(&output)["output"]["settings"]["level"], "patch");
+
+    let output = bump(tmp.path(), &["snapshot", "--json"]);
+    assert!(output.status.success());
+    assert!(report(&output)["output"]["version"]
+        .as_str()
+        .unwrap()
+        .starts_with("1.2.4-nightly."));
+}
+
+#[test]
+fn json_report_of_usage_and_config_errors() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.2.3");
+
+    let output = bump(tmp.path(), &["--json", "--bogus"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        report(&output)["error"],
+        "Found argument '--bogus' which wasn't expected, or isn't valid in this context"
+    );
+
+    let output = bump(tmp.path(), &["patch", "--json", "-f", "missing.json"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        report(&output)["error"],
+        "Failed to bump: missing.json: No such file or directory (os error 2)"
+    );
+
+    std::fs::write(tmp.path().join(".manifest-bump.toml"), "level = 3\n").unwrap();
+    let output = bump(tmp.path(), &["patch", "--json"]);
+    assert!(!output.status.success());
+    assert!(report(&output)["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("Failed to load config: "));
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn parts_above_u16_are_fine_outside_chrome_mode() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.2.65535");
+
+    let output = bump(tmp.path(), &["patch"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.65536\""));
+}
+
+#[test]
+fn chrome_mode_rejects_parts_above_65535() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.2.65535");
+
+    let output = bump(tmp.path(), &["patch", "--chrome"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1.2.65536 has a part over 65535"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.65535\""));
+}
+
+#[test]
+fn overflowing_part_is_an_error() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.2.18446744073709551615");
+
+    let output = bump(tmp.path(), &["patch"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.starts_with("error: Failed to bump: "));
+    assert!(stderr.contains("cannot be incremented"));
+    assert!(!stderr.contains("panicked"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.18446744073709551615\""));
+}
+
+#[test]
+fn too_large_version_in_the_file_is_an_error() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "99999999999999999999.0.0");
+
+    let output = bump(tmp.path(), &["patch"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(
+        "manifest.json: invalid version: \"99999999999999999999.0.0\" \
+         (99999999999999999999 is too large for a version part"
+    ));
+}
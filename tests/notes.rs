@@ -0,0 +1,39 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn notes_from_commits_between_tags() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    git(tmp.path(), &["tag", "v0.1.0"]);
+    empty_commit(tmp.path(), "feat: one");
+    git(tmp.path(), &["tag", "v0.2.0"]);
+    empty_commit(tmp.path(), "fix: two");
+
+    let output = bump(tmp.path(), &["notes", "0.2.0", "--format", "json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["version"], "0.2.0");
+    assert_eq!(json["source"], "commits");
+    assert_eq!(json["sections"][0]["title"], "Added");
+    assert_eq!(json["sections"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn notes_from_changelog() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.0.0");
+    std::fs::write(
+        tmp.path().join("CHANGELOG.md"),
+        "# Changelog\n\n## v1.0.0 - 2026-10-01\n\n### Added\n\n- everything\n",
+    )
+    .unwrap();
+
+    let output = bump(tmp.path(), &["notes"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "### Added\n\n- everything\n"
+    );
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn github_output_is_appended() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+    let github_output = tmp.path().join("github_output");
+    std::fs::write(&github_output, "earlier=1\n").unwrap();
+
+    let output = bump_with_env(
+        tmp.path(),
+        &[("GITHUB_OUTPUT", github_output.to_str().unwrap())],
+        &["patch", "-g", "--changelog"],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read_to_string(&github_output).unwrap(),
+        "earlier=1\nold_version=1.2.3\nnew_version=1.2.4\ntag=v1.2.4\n\
+         changed_files=manifest.json CHANGELOG.md\n"
+    );
+}
+
+#[test]
+fn output_env_writes_a_dotenv_file() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.2.3");
+
+    let output = bump(tmp.path(), &["minor", "--output-env", "bump.env"]);
+    assert!(output.status.success());
+    assert_eq!(
+        read(tmp.path(), "bump.env"),
+        "OLD_VERSION=\"1.2.3\"\nNEW_VERSION=\"1.3.0\"\nTAG=\"v1.3.0\"\n\
+         CHANGED_FILES=\"manifest.json\"\n"
+    );
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use std::path::Path;
+
+use common::*;
+
+/// Lay out a small extension: sources, a source map, an ignored directory and a dotfile.
+fn write_extension(dir: &Path) {
+    write_manifest(dir, "1.2.3");
+    for (path, contents) in [
+        ("src/background.js", "console.log(1);\n"),
+        ("src/background.js.map", "{}\n"),
+        ("icons/48.png", "png\n"),
+        ("node_modules/dep/index.js", "dep\n"),
+        (".gitignore", "node_modules/\n"),
+        (".env", "SECRET=1\n"),
+    ] {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+}
+
+fn entries(zip: &Path) -> Vec<String> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(zip).unwrap()).unwrap();
+    (0..archive.len())
+        .map(|i| archive.by_index(i).unwrap().name().to_string())
+        .collect()
+}
+
+#[test]
+fn pack_honours_gitignore_and_globs() {
+    let tmp = TempDir::new();
+    write_extension(tmp.path());
+
+    let output = bump(tmp.path(), &["pack", "--exclude", "*.map"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "dist/test-1.2.3.zip\n"
+    );
+    assert_eq!(
+        entries(&tmp.path().join("dist/test-1.2.3.zip")),
+        ["icons/48.png", "manifest.json", "src/background.js"]
+    );
+
+    let output = bump(
+        tmp.path(),
+        &["pack", "--include", "manifest.json", "--include", "src/**"],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        entries(&tmp.path().join("dist/test-1.2.3.zip")),
+        [
+            "manifest.json",
+            "src/background.js",
+            "src/background.js.map"
+        ]
+    );
+}
+
+#[test]
+fn pack_is_reproducible() {
+    let tmp = TempDir::new();
+    write_extension(tmp.path());
+    let zip = tmp.path().join("dist/test-1.2.3.zip");
+
+    assert!(bump(tmp.path(), &["pack"]).status.success());
+    let first = std::fs::read(&zip).unwrap();
+    std::fs::write(tmp.path().join("src/background.js"), "console.log(1);\n").unwrap();
+    assert!(bump(tmp.path(), &["pack"]).status.success());
+    assert_eq!(std::fs::read(&zip).unwrap(), first);
+}
+
+#[test]
+fn pack_after_bump() {
+    let tmp = TempDir::new();
+    write_extension(tmp.path());
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "[pack]\nexclude = [\"*.map\"]\nout-dir = \"build\"\n",
+    )
+    .unwrap();
+
+    let output = bump(tmp.path(), &["minor", "--pack"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("packed build/test-1.3.0.zip"));
+    let zip = tmp.path().join("build/test-1.3.0.zip");
+    assert_eq!(
+        entries(&zip),
+        ["icons/48.png", "manifest.json", "src/background.js"]
+    );
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(zip).unwrap()).unwrap();
+    let mut manifest = String::new();
+    std::io::Read::read_to_string(
+        &mut archive.by_name("manifest.json").unwrap(),
+        &mut manifest,
+    )
+    .unwrap();
+    assert!(manifest.contains("\"1.3.0\""));
+}
+
+#[test]
+fn failed_bump_removes_the_zip() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+    write_extension(tmp.path());
+
+    let output = bump(
+        tmp.path(),
+        &["minor", "-g", "--pack", "--pre-commit", "false"],
+    );
+    assert!(!output.status.success());
+    assert!(!tmp.path().join("dist/test-1.3.0.zip").exists());
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.3\""));
+}
+
+#[test]
+fn subcommand_prefixes_are_not_accepted() {
+    let tmp = TempDir::new();
+    write_extension(tmp.path());
+
+    let output = bump(tmp.path(), &["p"]);
+    assert!(!output.status.success());
+    assert!(!tmp.path().join("dist").exists());
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.3\""));
+
+    let output = bump(tmp.path(), &["patch"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.4\""));
+}
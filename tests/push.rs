@@ -0,0 +1,73 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn push_branch_and_tag() {
+    let tmp = TempDir::new();
+    let (work, remote) = init_with_remote(tmp.path(), "0.1.0");
+
+    let output = bump(&work, &["minor", "-g", "--push"]);
+    assert!(output.status.success());
+
+    assert_eq!(git(&remote, &["tag", "--list"]), "v0.2.0\n");
+    assert_eq!(
+        git(&remote, &["rev-parse", "main"]),
+        git(&work, &["rev-parse", "HEAD"])
+    );
+}
+
+#[test]
+fn push_to_named_remote() {
+    let tmp = TempDir::new();
+    let (work, remote) = init_with_remote(tmp.path(), "0.1.0");
+    git(&work, &["remote", "rename", "origin", "upstream"]);
+
+    let output = bump(&work, &["-g", "--push=upstream"]);
+    assert!(output.status.success());
+
+    assert_eq!(git(&remote, &["tag", "--list"]), "v0.1.1\n");
+}
+
+#[test]
+fn rejected_push_rolls_back() {
+    let tmp = TempDir::new();
+    let (work, remote) = init_with_remote(tmp.path(), "0.1.0");
+    let other = tmp.path().join("other");
+    git(
+        tmp.path(),
+        &[
+            "clone",
+            "-q",
+            remote.to_str().unwrap(),
+            other.to_str().unwrap(),
+        ],
+    );
+    std::fs::write(other.join("README.md"), "diverged\n").unwrap();
+    git(&other, &["add", "README.md"]);
+    git(&other, &["commit", "-q", "-m", "diverge"]);
+    git(&other, &["push", "-q", "origin", "main"]);
+    let head = git(&work, &["rev-parse", "HEAD"]);
+
+    let output = bump(&work, &["-g", "--push", "--rollback-on-failure"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("was rejected"));
+
+    assert_eq!(git(&remote, &["tag", "--list"]), "");
+    assert_eq!(git(&work, &["tag", "--list"]), "");
+    assert_eq!(git(&work, &["rev-parse", "HEAD"]), head);
+    assert!(read(&work, "manifest.json").contains("\"0.1.0\""));
+}
+
+#[test]
+fn rejected_push_keeps_commit_without_rollback() {
+    let tmp = TempDir::new();
+    let (work, remote) = init_with_remote(tmp.path(), "0.1.0");
+    git(&remote, &["tag", "v0.1.1", "main"]);
+
+    let output = bump(&work, &["-g", "--push"]);
+    assert!(!output.status.success());
+
+    assert_eq!(git(&work, &["tag", "--list"]), "v0.1.1\n");
+    assert!(read(&work, "manifest.json").contains("\"0.1.1\""));
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use common::*;
+
+const TODAY: &str = "2026-10-17";
+
+#[test]
+fn snapshot_prints_a_prerelease_without_touching_git() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+    let hash = git(tmp.path(), &["rev-parse", "--short", "HEAD"]);
+    let hash = hash.trim();
+    let hash = if hash.chars().all(|c| c.is_ascii_digit()) {
+        format!("g{}", hash)
+    } else {
+        hash.to_string()
+    };
+
+    let output = bump_at(tmp.path(), TODAY, &["snapshot", "minor"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("1.3.0-nightly.20261017.{}\n", hash)
+    );
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.3\""));
+    assert_eq!(git(tmp.path(), &["status", "--porcelain"]), "");
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "");
+}
+
+#[test]
+fn snapshot_write_outside_git() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.2.3");
+
+    let output = bump_at(
+        tmp.path(),
+        TODAY,
+        &["snapshot", "--preid", "dev", "--write"],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "1.2.4-dev.20261017\n"
+    );
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.4-dev.20261017\""));
+}
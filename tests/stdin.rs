@@ -0,0 +1,122 @@
+mod common;
+
+use common::*;
+
+const MANIFEST: &str = "{\n  \"name\": \"test\",\n  \"version\": \"1.2.3\"\n}\n";
+
+#[test]
+fn stdin_to_stdout() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "git = true\n[hooks]\npost-bump = [\"echo hook\"]\n",
+    )
+    .unwrap();
+
+    let output = bump_with_input(tmp.path(), &["minor", "-f", "-"], MANIFEST);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        MANIFEST.replace("1.2.3", "1.3.0")
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("hook\n"));
+    assert!(stderr.ends_with("v1.2.3 -> v1.3.0\n"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"0.1.0\""));
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "");
+    assert_eq!(git(tmp.path(), &["log", "--format=%s"]), "initial\n");
+}
+
+#[test]
+fn stdin_rejects_git() {
+    let tmp = TempDir::new();
+
+    let output = bump_with_input(tmp.path(), &["minor", "-f", "-", "-g"], MANIFEST);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--git cannot be used with -f -"));
+}
+
+#[test]
+fn stdin_rejects_side_effects() {
+    let tmp = TempDir::new();
+
+    for args in [
+        &["--changelog"][..],
+        &["--pack"],
+        &["--include", "*.js"],
+        &["--exclude", "*.map"],
+        &["--from-tag"],
+        &["--package", "popup"],
+        &["--changed"],
+        &["--commit-per-package"],
+    ] {
+        let mut argv = vec!["minor", "-f", "-"];
+        argv.extend(args);
+        let output = bump_with_input(tmp.path(), &argv, MANIFEST);
+        assert!(!output.status.success());
+        let message = format!("{} cannot be used with -f -", args[0]);
+        assert!(String::from_utf8_lossy(&output.stderr).contains(&message));
+        assert!(output.stdout.is_empty());
+    }
+    assert!(!tmp.path().join("CHANGELOG.md").exists());
+}
+
+#[test]
+fn stdin_skips_configured_side_effects() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+    git(tmp.path(), &["tag", "v0.1.0"]);
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "changelog = \"CHANGELOG.md\"\n\
+         [pack]\non-bump = true\n\n\
+         [updates]\nurl = \"https://example.com/{zip}\"\nfirefox = \"updates.json\"\n\
+         firefox-id = \"test@example.com\"\n\n\
+         [workspace]\npackages = [\"packages/*\"]\n",
+    )
+    .unwrap();
+
+    let output = bump_with_input(tmp.path(), &["minor", "-f", "-"], MANIFEST);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        MANIFEST.replace("1.2.3", "1.3.0")
+    );
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("tag"));
+    for file in ["CHANGELOG.md", "dist", "updates.json"] {
+        assert!(!tmp.path().join(file).exists(), "{} was written", file);
+    }
+}
+
+#[test]
+fn output_rejects_git() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.0.0");
+
+    let output = bump(tmp.path(), &["minor", "-g", "--output", "out.json"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--output cannot be used with git"));
+
+    std::fs::write(tmp.path().join(".manifest-bump.toml"), "git = true\n").unwrap();
+    let output = bump(tmp.path(), &["minor", "--output", "out.json"]);
+    assert!(!output.status.success());
+    assert!(!tmp.path().join("out.json").exists());
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "");
+
+    let output = bump(tmp.path(), &["minor", "--no-git", "--output", "out.json"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "out.json").contains("\"1.1.0\""));
+}
+
+#[test]
+fn output_leaves_the_original_untouched() {
+    let tmp = TempDir::new();
+    std::fs::write(tmp.path().join("manifest.json"), MANIFEST).unwrap();
+
+    std::fs::create_dir(tmp.path().join("dist")).unwrap();
+    let output = bump(tmp.path(), &["patch", "--output", "dist/manifest.json"]);
+    assert!(output.status.success());
+    assert_eq!(read(tmp.path(), "manifest.json"), MANIFEST);
+    assert!(read(tmp.path(), "dist/manifest.json").contains("\"1.2.4\""));
+}
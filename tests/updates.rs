@@ -0,0 +1,89 @@
+mod common;
+
+use common::*;
+
+const MANIFEST: &str = r#"{
+  "manifest_version": 2,
+  "name": "test",
+  "version": "1.2.3",
+  "browser_specific_settings": { "gecko": { "id": "test@example.com" } }
+}
+"#;
+
+const UPDATES: &str = r#"{
+  "addons": {
+    "test@example.com": {
+      "updates": [
+        { "version": "1.2.3", "update_link": "https://example.com/test-1.2.3.zip" }
+      ]
+    }
+  }
+}
+"#;
+
+#[test]
+fn update_manifests_get_the_new_version() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "1.2.3");
+    std::fs::write(tmp.path().join("manifest.json"), MANIFEST).unwrap();
+    std::fs::write(tmp.path().join("updates.json"), UPDATES).unwrap();
+    std::fs::write(tmp.path().join(".gitignore"), "dist/\n").unwrap();
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "[pack]\non-bump = true\n\n\
+         [updates]\nurl = \"https://example.com/{zip}\"\nfirefox = \"updates.json\"\n\
+         chrome = \"update.xml\"\nchrome-id = \"abcdefghijklmnopabcdefghijklmnop\"\nhash = true\n",
+    )
+    .unwrap();
+    git(tmp.path(), &["add", "-A"]);
+    git(tmp.path(), &["commit", "-q", "-m", "setup"]);
+
+    let output = bump(tmp.path(), &["minor", "-g"]);
+    assert!(output.status.success());
+
+    let zip = std::fs::read(tmp.path().join("dist/test-1.3.0.zip")).unwrap();
+    let hash: String = {
+        use sha2::Digest;
+        sha2::Sha256::digest(zip)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    };
+    let updates = read(tmp.path(), "updates.json");
+    assert!(updates.starts_with(
+        "{\n  \"addons\": {\n    \"test@example.com\": {\n      \"updates\": [\n        \
+         { \"version\": \"1.2.3\", \"update_link\": \"https://example.com/test-1.2.3.zip\" },\n        {\n"
+    ));
+    let value: serde_json::Value = serde_json::from_str(&updates).unwrap();
+    assert_eq!(
+        value["addons"]["test@example.com"]["updates"][1],
+        serde_json::json!({
+            "version": "1.3.0",
+            "update_link": "https://example.com/test-1.3.0.zip",
+            "update_hash": format!("sha256:{}", hash),
+        })
+    );
+    assert!(read(tmp.path(), "update.xml").contains(&format!(
+        "<updatecheck codebase='https://example.com/test-1.3.0.zip' version='1.3.0' \
+         hash_sha256='{}' />",
+        hash
+    )));
+
+    let committed = git(tmp.path(), &["show", "--name-only", "--format=", "HEAD"]);
+    assert_eq!(committed, "manifest.json\nupdate.xml\nupdates.json\n");
+}
+
+#[test]
+fn hash_requires_packing() {
+    let tmp = TempDir::new();
+    std::fs::write(tmp.path().join("manifest.json"), MANIFEST).unwrap();
+    std::fs::write(
+        tmp.path().join(".manifest-bump.toml"),
+        "[updates]\nurl = \"https://example.com/{zip}\"\nfirefox = \"updates.json\"\nhash = true\n",
+    )
+    .unwrap();
+
+    let output = bump(tmp.path(), &["minor"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("`updates.hash` needs the zip"));
+}
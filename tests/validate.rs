@@ -0,0 +1,44 @@
+mod common;
+
+use common::*;
+
+const MANIFEST: &str = r#"{
+  "manifest_version": 3,
+  "name": "test",
+  "version": "1.2.3"
+}
+"#;
+
+#[test]
+fn valid_manifest_is_bumped() {
+    let tmp = TempDir::new();
+    std::fs::write(tmp.path().join("manifest.json"), MANIFEST).unwrap();
+
+    let output = bump(tmp.path(), &["patch", "--validate"]);
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.4\""));
+}
+
+#[test]
+fn invalid_new_version_fails_before_writing() {
+    let tmp = TempDir::new();
+    std::fs::write(tmp.path().join("manifest.json"), MANIFEST).unwrap();
+
+    let output = bump(tmp.path(), &["prepatch", "--validate"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("version `1.2.4-rc.0` must be dot-separated integers"));
+    assert_eq!(read(tmp.path(), "manifest.json"), MANIFEST);
+}
+
+#[test]
+fn warn_mode_reports_and_continues() {
+    let tmp = TempDir::new();
+    write_manifest(tmp.path(), "1.2.3");
+
+    let output = bump(tmp.path(), &["patch", "--validate=warn"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("warning: manifest.json: missing manifest_version"));
+    assert!(read(tmp.path(), "manifest.json").contains("\"1.2.4\""));
+}
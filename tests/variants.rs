@@ -0,0 +1,69 @@
+mod common;
+
+use std::path::Path;
+
+use common::*;
+
+const CHROME: &str = r#"{
+  "manifest_version": 3,
+  "name": "test",
+  "version": "1.2.3",
+  "background": { "service_worker": "background.js" }
+}
+"#;
+
+fn firefox(version: &str) -> String {
+    format!(
+        r#"{{
+  "manifest_version": 2,
+  "name": "test",
+  "version": "{}",
+  "browser_specific_settings": {{
+    "gecko": {{ "id": "test@example.com", "strict_min_version": "109.0" }}
+  }}
+}}
+"#,
+        version
+    )
+}
+
+fn write_variants(dir: &Path, firefox_version: &str) {
+    std::fs::write(dir.join("manifest.chrome.json"), CHROME).unwrap();
+    std::fs::write(dir.join("manifest.firefox.json"), firefox(firefox_version)).unwrap();
+    std::fs::write(
+        dir.join(".manifest-bump.toml"),
+        "variants = [\"manifest.chrome.json\", \"manifest.firefox.json\"]\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn variants_are_bumped_together() {
+    let tmp = TempDir::new();
+    write_variants(tmp.path(), "1.2.3");
+
+    let output = bump(tmp.path(), &["minor", "--validate"]);
+    assert!(output.status.success());
+    assert_eq!(
+        read(tmp.path(), "manifest.chrome.json").trim_end(),
+        CHROME.replace("1.2.3", "1.3.0").trim_end()
+    );
+    assert_eq!(
+        read(tmp.path(), "manifest.firefox.json").trim_end(),
+        firefox("1.3.0").trim_end()
+    );
+}
+
+#[test]
+fn out_of_sync_variants_are_rejected() {
+    let tmp = TempDir::new();
+    write_variants(tmp.path(), "1.2.2");
+
+    let output = bump(tmp.path(), &["minor"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(
+        "variants are out of sync: manifest.chrome.json is at 1.2.3 but manifest.firefox.json is at 1.2.2"
+    ));
+    assert_eq!(read(tmp.path(), "manifest.chrome.json"), CHROME);
+    assert_eq!(read(tmp.path(), "manifest.firefox.json"), firefox("1.2.2"));
+}
@@ -0,0 +1,200 @@
+mod common;
+
+use std::path::Path;
+
+use common::*;
+
+fn init_workspace(dir: &Path, extra_config: &str) {
+    init_repo(dir, "0.1.0");
+    for name in ["options", "popup"] {
+        let package = dir.join("packages").join(name);
+        std::fs::create_dir_all(&package).unwrap();
+        write_manifest(&package, "1.1.0");
+    }
+    std::fs::write(
+        dir.join(".manifest-bump.toml"),
+        format!(
+            "git = true\n{}\n[workspace]\npackages = [\"packages/*\"]\n",
+            extra_config
+        ),
+    )
+    .unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "workspace"]);
+}
+
+#[test]
+fn bump_single_package() {
+    let tmp = TempDir::new();
+    init_workspace(tmp.path(), "");
+
+    let output = bump(tmp.path(), &["minor", "--package", "popup"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("popup: v1.1.0 -> v1.2.0\n"));
+    assert!(read(tmp.path(), "packages/popup/manifest.json").contains("\"1.2.0\""));
+    assert!(read(tmp.path(), "packages/options/manifest.json").contains("\"1.1.0\""));
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "popup@1.2.0\n");
+    assert_eq!(
+        git(tmp.path(), &["log", "-1", "--format=%s"]),
+        "📚 release popup@1.2.0\n"
+    );
+}
+
+#[test]
+fn bump_all_packages_in_one_commit() {
+    let tmp = TempDir::new();
+    init_workspace(tmp.path(), "");
+
+    let output = bump(tmp.path(), &["patch"]);
+    assert!(output.status.success());
+    assert_eq!(
+        git(tmp.path(), &["tag", "--list"]),
+        "options@1.1.1\npopup@1.1.1\n"
+    );
+    assert_eq!(
+        git(tmp.path(), &["log", "--format=%s"]),
+        "📚 release options@1.1.1, popup@1.1.1\nworkspace\ninitial\n"
+    );
+}
+
+#[test]
+fn bump_all_packages_with_commit_per_package() {
+    let tmp = TempDir::new();
+    init_workspace(tmp.path(), "");
+
+    let output = bump(tmp.path(), &["patch", "--commit-per-package"]);
+    assert!(output.status.success());
+    assert_eq!(
+        git(tmp.path(), &["log", "--format=%s %D"]),
+        "📚 release popup@1.1.1 HEAD -> main, tag: popup@1.1.1\n\
+         📚 release options@1.1.1 tag: options@1.1.1\n\
+         workspace \n\
+         initial \n"
+    );
+}
+
+#[test]
+fn unknown_package_is_rejected() {
+    let tmp = TempDir::new();
+    init_workspace(tmp.path(), "");
+
+    let output = bump(tmp.path(), &["patch", "--package", "sidebar"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown package `sidebar`"));
+    assert_eq!(git(tmp.path(), &["tag", "--list"]), "");
+}
+
+#[test]
+fn package_option_requires_workspace() {
+    let tmp = TempDir::new();
+    init_repo(tmp.path(), "0.1.0");
+
+    let output = bump(tmp.path(), &["patch", "--package", "popup"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("requires a [workspace]"));
+}
+
+#[test]
+fn changed_bumps_only_packages_with_new_commits() {
+    let tmp = TempDir::new();
+    init_workspace(tmp.path(), "");
+    git(tmp.path(), &["tag", "options@1.1.0"]);
+    git(tmp.path(), &["tag", "popup@1.1.0"]);
+    std::fs::write(tmp.path().join("packages/popup/popup.js"), "").unwrap();
+    git(tmp.path(), &["add", "."]);
+    git(tmp.path(), &["commit", "-q", "-m", "fix: popup"]);
+
+    let output = bump(tmp.path(), &["patch", "--changed"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with(
+        "package  status   version\n\
+         options  skipped  v1.1.0\n\
+         popup    bumped   v1.1.0 -> v1.1.1\n"
+    ));
+    assert!(read(tmp.path(), "packages/options/manifest.json").contains("\"1.1.0\""));
+    assert!(read(tmp.path(), "packages/popup/manifest.json").contains("\"1.1.1\""));
+
+    let output = bump(tmp.path(), &["patch", "--changed"]);
+    assert!(output.status.success());
+    assert_eq!(
+        git(tmp.path(), &["tag", "--list"]),
+        "options@1.1.0\npopup@1.1.0\npopup@1.1.1\n"
+    );
+}
+
+#[test]
+fn outputs_are_prefixed_with_the_package() {
+    let tmp = TempDir::new();
+    init_workspace(tmp.path(), "");
+
+    let output = bump(
+        tmp.path(),
+        &["minor", "--package", "popup", "--output-env", "bump.env"],
+    );
+    assert!(output.status.success());
+    let env = read(tmp.path(), "bump.env");
+    assert!(env.contains("POPUP_NEW_VERSION=\"1.2.0\"\nPOPUP_TAG=\"popup@1.2.0\"\n"));
+    assert!(env.contains("POPUP_CHANGED_FILES=\"packages/popup/manifest.json\"\n"));
+}
+
+#[test]
+fn output_needs_a_single_package() {
+    let tmp = TempDir::new();
+    init_workspace(tmp.path(), "");
+
+    let output = bump(tmp.path(), &["patch", "--no-git", "--output", "out.json"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--output needs a single file"));
+    assert!(!tmp.path().join("out.json").exists());
+
+    let output = bump(
+        tmp.path(),
+        &["patch", "--no-git", "-p", "popup", "--output", "out.json"],
+    );
+    assert!(output.status.success());
+    assert!(read(tmp.path(), "out.json").contains("\"1.1.1\""));
+    assert!(read(tmp.path(), "packages/popup/manifest.json").contains("\"1.1.0\""));
+}
+
+#[test]
+fn subcommands_read_the_named_package() {
+    let tmp = TempDir::new();
+    init_workspace(tmp.path(), "");
+    std::fs::write(tmp.path().join("packages/options/options.js"), "1\n").unwrap();
+    git(tmp.path(), &["add", "."]);
+    git(tmp.path(), &["commit", "-q", "-m", "fix: options thing"]);
+    std::fs::write(tmp.path().join("packages/popup/popup.js"), "1\n").unwrap();
+    git(tmp.path(), &["add", "."]);
+    git(tmp.path(), &["commit", "-q", "-m", "feat: popup thing"]);
+    git(tmp.path(), &["tag", "popup@1.1.0"]);
+
+    let output = bump(tmp.path(), &["notes", "--package", "popup"]);
+    assert!(output.status.success());
+    let notes = String::from_utf8_lossy(&output.stdout);
+    assert!(notes.contains("popup thing"));
+    assert!(!notes.contains("options thing"));
+
+    let output = bump(
+        tmp.path(),
+        &["check", "--satisfies", "^1.1", "-p", "options"],
+    );
+    assert!(output.status.success());
+
+    let output = bump(tmp.path(), &["notes"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--package is required in a workspace")
+    );
+}
+
+#[test]
+fn snapshot_of_a_package() {
+    let tmp = TempDir::new();
+    init_workspace(tmp.path(), "");
+
+    let output = bump(tmp.path(), &["snapshot", "minor", "-p", "popup", "--write"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("1.2.0-nightly."));
+    assert!(read(tmp.path(), "packages/popup/manifest.json").contains("1.2.0-nightly."));
+    assert!(read(tmp.path(), "packages/options/manifest.json").contains("\"1.1.0\""));
+}